@@ -0,0 +1,82 @@
+//! Inter-task telemetry bus
+//!
+//! Tasks on the base station (`height_control_task`, `safety_monitor_task`,
+//! and the future `wpt_control_task`/`sensor_monitor_task`) need to share
+//! live state without polling each other's hardware or reaching into each
+//! other's internals. This follows the PX4 uORB pattern: each kind of
+//! state is a typed topic, publishers push non-blocking updates, and
+//! subscribers read whatever the latest sample is.
+//!
+//! Every topic is a statically-allocated `embassy_sync::pubsub::PubSubChannel`
+//! with a small bounded queue. Publishing is lossy-latest: if a subscriber
+//! falls behind, the oldest queued sample is dropped rather than blocking
+//! the publisher, since a 100Hz control loop must never stall waiting for
+//! a slow reader.
+
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::pubsub::PubSubChannel;
+
+use crate::levitation::LevitationMode;
+
+/// Depth of each topic's queue (samples held before the oldest is dropped)
+const QUEUE_DEPTH: usize = 4;
+
+/// Maximum number of subscribers per topic
+const MAX_SUBSCRIBERS: usize = 4;
+
+/// Maximum number of publishers per topic
+const MAX_PUBLISHERS: usize = 1;
+
+/// Live levitation height/velocity/mode, published by `height_control_task`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeightState {
+    /// Current height above base (mm)
+    pub height_mm: f32,
+    /// Vertical velocity (mm/s, positive = rising)
+    pub velocity_mm_s: f32,
+    /// Current operating mode
+    pub mode: LevitationMode,
+    /// Last commanded DAC voltage
+    pub dac_voltage: f32,
+}
+
+/// Live thermal readings, published by `sensor_monitor_task`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThermalState {
+    /// Electromagnet coil temperature (Celsius)
+    pub coil_temp_c: f32,
+    /// NTC thermistor readings around the base (Celsius)
+    pub ntc_temps_c: [f32; 4],
+}
+
+/// Live power/WPT status, published by `wpt_control_task`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PowerState {
+    /// Power supply rail within spec
+    pub supply_ok: bool,
+    /// Current wireless power transfer efficiency estimate [0, 1]
+    pub wpt_efficiency: f32,
+}
+
+/// Channel type for a topic carrying `T`
+pub type Topic<T> = PubSubChannel<CriticalSectionRawMutex, T, QUEUE_DEPTH, MAX_SUBSCRIBERS, MAX_PUBLISHERS>;
+
+/// `HeightState` topic
+pub static HEIGHT_STATE: Topic<HeightState> = PubSubChannel::new();
+
+/// `ThermalState` topic
+pub static THERMAL_STATE: Topic<ThermalState> = PubSubChannel::new();
+
+/// `PowerState` topic
+pub static POWER_STATE: Topic<PowerState> = PubSubChannel::new();
+
+/// Publish a sample to a topic, dropping the oldest queued sample on overflow
+///
+/// This never blocks: a publisher on a 100Hz loop cannot wait on a slow
+/// subscriber, so we use `publish_immediate` rather than `publish().await`.
+pub fn publish<T>(topic: &Topic<T>, value: T)
+where
+    T: Clone,
+{
+    topic.publish_immediate(value);
+}
@@ -0,0 +1,223 @@
+//! Pluggable DAC output HAL
+//!
+//! `HeightController::update` produces a target DAC voltage, but the part
+//! that turns a voltage into a bus transaction shouldn't be baked into the
+//! control loop -- different boards may only have an MCP4725 on I2C, or
+//! may want a finer-grained converter for smoother levitation. `DacOutput`
+//! abstracts "command a voltage", with quantization to the part's own
+//! resolution and the full-scale range check living entirely inside the
+//! implementation.
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::i2c::I2c;
+
+/// Errors from a `DacOutput` implementation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DacError {
+    /// The underlying bus (I2C/SPI) reported an error
+    BusError,
+    /// Requested voltage is outside the DAC's full-scale range
+    OutOfRange,
+}
+
+/// A DAC that can be commanded to output a given voltage
+pub trait DacOutput {
+    /// Set the DAC output to `volts`
+    ///
+    /// Implementations quantize to their own resolution and return
+    /// `DacError::OutOfRange` rather than silently clamping, so a
+    /// miscalibrated caller is caught instead of masked.
+    fn set_voltage(&mut self, volts: f32) -> Result<(), DacError>;
+
+    /// Full-scale output voltage (reference voltage)
+    fn full_scale(&self) -> f32;
+
+    /// DAC resolution in bits
+    fn resolution_bits(&self) -> u8;
+
+    /// Convert a normalized command in `[0, 1]` to raw counts for this DAC
+    fn counts_for(&self, normalized: f32) -> u32 {
+        let max_count = (1u32 << self.resolution_bits()) - 1;
+        (normalized.clamp(0.0, 1.0) * max_count as f32) as u32
+    }
+}
+
+/// MCP4725 12-bit DAC over I2C
+///
+/// The default part on the base station board; fast-mode write, no EEPROM
+/// programming (the power-on default is irrelevant since the control loop
+/// sets a fresh voltage every cycle).
+pub struct Mcp4725Dac<I2C> {
+    i2c: I2C,
+    address: u8,
+    vref: f32,
+}
+
+impl<I2C: I2c> Mcp4725Dac<I2C> {
+    /// MCP4725 resolution (bits)
+    pub const RESOLUTION_BITS: u8 = 12;
+
+    /// Create a new driver for the DAC at `address`, referenced to `vref` volts
+    pub fn new(i2c: I2C, address: u8, vref: f32) -> Self {
+        Self { i2c, address, vref }
+    }
+}
+
+impl<I2C: I2c> DacOutput for Mcp4725Dac<I2C> {
+    fn set_voltage(&mut self, volts: f32) -> Result<(), DacError> {
+        if volts < 0.0 || volts > self.vref {
+            return Err(DacError::OutOfRange);
+        }
+
+        let counts = self.counts_for(volts / self.vref) as u16;
+
+        // Fast-mode write: 2 bytes, top nibble is the PD/command bits (0 = normal mode)
+        let buf = [((counts >> 8) & 0x0F) as u8, (counts & 0xFF) as u8];
+        self.i2c.write(self.address, &buf).map_err(|_| DacError::BusError)
+    }
+
+    fn full_scale(&self) -> f32 {
+        self.vref
+    }
+
+    fn resolution_bits(&self) -> u8 {
+        Self::RESOLUTION_BITS
+    }
+}
+
+/// Bit-banged software SPI bus driving SCLK/MOSI/SYNC as plain GPIOs
+///
+/// For boards with no spare hardware SPI peripheral to dedicate to a DAC,
+/// following the thermostat project's migration of the AD5680 to soft-SPI.
+pub struct SoftSpi<Sclk, Mosi, Sync, Delay> {
+    sclk: Sclk,
+    mosi: Mosi,
+    sync: Sync,
+    delay: Delay,
+}
+
+impl<Sclk, Mosi, Sync, Delay> SoftSpi<Sclk, Mosi, Sync, Delay>
+where
+    Sclk: OutputPin,
+    Mosi: OutputPin,
+    Sync: OutputPin,
+    Delay: DelayNs,
+{
+    /// Half-period delay between SPI edges
+    const HALF_PERIOD_NS: u32 = 50;
+
+    /// Create a new software SPI bus over the given pins
+    pub fn new(sclk: Sclk, mosi: Mosi, sync: Sync, delay: Delay) -> Self {
+        Self { sclk, mosi, sync, delay }
+    }
+
+    /// Shift `bits` bits of `value` out MSB-first, toggling SYNC around the frame
+    fn write_bits(&mut self, value: u32, bits: u8) -> Result<(), DacError> {
+        self.sync.set_low().map_err(|_| DacError::BusError)?;
+
+        for i in (0..bits).rev() {
+            if (value >> i) & 1 == 1 {
+                self.mosi.set_high().map_err(|_| DacError::BusError)?;
+            } else {
+                self.mosi.set_low().map_err(|_| DacError::BusError)?;
+            }
+            self.delay.delay_ns(Self::HALF_PERIOD_NS);
+            self.sclk.set_high().map_err(|_| DacError::BusError)?;
+            self.delay.delay_ns(Self::HALF_PERIOD_NS);
+            self.sclk.set_low().map_err(|_| DacError::BusError)?;
+        }
+
+        self.sync.set_high().map_err(|_| DacError::BusError)
+    }
+}
+
+/// AD5680-class 18-bit DAC over bit-banged software SPI
+///
+/// Gives a finer-grained levitation current command than the MCP4725 on
+/// boards without a spare hardware SPI peripheral.
+pub struct Ad5680Dac<Sclk, Mosi, Sync, Delay> {
+    spi: SoftSpi<Sclk, Mosi, Sync, Delay>,
+    vref: f32,
+}
+
+impl<Sclk, Mosi, Sync, Delay> Ad5680Dac<Sclk, Mosi, Sync, Delay>
+where
+    Sclk: OutputPin,
+    Mosi: OutputPin,
+    Sync: OutputPin,
+    Delay: DelayNs,
+{
+    /// AD5680 resolution (bits)
+    pub const RESOLUTION_BITS: u8 = 18;
+
+    /// Create a new driver over the given soft-SPI pins, referenced to `vref` volts
+    pub fn new(sclk: Sclk, mosi: Mosi, sync: Sync, delay: Delay, vref: f32) -> Self {
+        Self { spi: SoftSpi::new(sclk, mosi, sync, delay), vref }
+    }
+}
+
+impl<Sclk, Mosi, Sync, Delay> DacOutput for Ad5680Dac<Sclk, Mosi, Sync, Delay>
+where
+    Sclk: OutputPin,
+    Mosi: OutputPin,
+    Sync: OutputPin,
+    Delay: DelayNs,
+{
+    fn set_voltage(&mut self, volts: f32) -> Result<(), DacError> {
+        if volts < 0.0 || volts > self.vref {
+            return Err(DacError::OutOfRange);
+        }
+
+        let counts = self.counts_for(volts / self.vref);
+
+        // AD5680 frame is 20 bits: 18 data bits followed by 2 don't-care LSBs
+        self.spi.write_bits(counts << 2, 20)
+    }
+
+    fn full_scale(&self) -> f32 {
+        self.vref
+    }
+
+    fn resolution_bits(&self) -> u8 {
+        Self::RESOLUTION_BITS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeDac {
+        resolution_bits: u8,
+        vref: f32,
+    }
+
+    impl DacOutput for FakeDac {
+        fn set_voltage(&mut self, _volts: f32) -> Result<(), DacError> {
+            Ok(())
+        }
+
+        fn full_scale(&self) -> f32 {
+            self.vref
+        }
+
+        fn resolution_bits(&self) -> u8 {
+            self.resolution_bits
+        }
+    }
+
+    #[test]
+    fn test_counts_for_full_scale() {
+        let dac = FakeDac { resolution_bits: 12, vref: 3.3 };
+        assert_eq!(dac.counts_for(0.0), 0);
+        assert_eq!(dac.counts_for(1.0), 4095);
+    }
+
+    #[test]
+    fn test_counts_for_higher_resolution_is_finer() {
+        let dac12 = FakeDac { resolution_bits: 12, vref: 3.3 };
+        let dac18 = FakeDac { resolution_bits: 18, vref: 3.3 };
+        assert!(dac18.counts_for(0.5) > dac12.counts_for(0.5) * 63);
+    }
+}
@@ -0,0 +1,128 @@
+//! OLED status display rendering
+//!
+//! Drives a small I2C OLED via `embedded-graphics` to give an at-a-glance
+//! local readout (height, velocity, mode, DAC command) without needing the
+//! hub connection. Modeled on the rotor-control project's display task,
+//! which toggles a filled status rectangle based on a `stopped` flag --
+//! here the banner region is inverted and flashed while an `EmergencyFlag`
+//! is latched.
+
+use embedded_graphics::mono_font::ascii::FONT_6X10;
+use embedded_graphics::mono_font::MonoTextStyle;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+use embedded_graphics::text::Text;
+
+use crate::levitation::LevitationMode;
+
+/// Display resolution (128x64 SSD1306, the common small I2C OLED)
+pub const DISPLAY_WIDTH: u32 = 128;
+pub const DISPLAY_HEIGHT: u32 = 64;
+
+/// Redraw rate for the status display (Hz)
+///
+/// Modest on purpose: the display must never compete with the 100Hz
+/// control loop for bus/CPU time.
+pub const DISPLAY_REFRESH_HZ: u32 = 15;
+
+/// Snapshot of the state the display renders each frame
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DisplaySnapshot {
+    /// Current height above base (mm)
+    pub height_mm: f32,
+    /// Vertical velocity (mm/s, positive = rising)
+    pub velocity_mm_s: f32,
+    /// Current operating mode
+    pub mode: LevitationMode,
+    /// Last commanded DAC voltage
+    pub dac_voltage: f32,
+    /// Whether the interrupt-latched emergency flag is set
+    pub emergency: bool,
+    /// Flips every redraw so the emergency banner flashes rather than just sitting solid
+    pub flash_phase: bool,
+}
+
+fn banner_label(mode: LevitationMode, emergency: bool) -> &'static str {
+    if emergency {
+        return "FAULT";
+    }
+    match mode {
+        LevitationMode::Lifted => "STOPPED",
+        LevitationMode::Charging { .. } => "CHARGING",
+        LevitationMode::EmergencyLanding => "FAULT",
+        _ => "LEVITATING",
+    }
+}
+
+/// Render one frame of the status display
+pub fn render<D>(display: &mut D, snapshot: &DisplaySnapshot) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    display.clear(BinaryColor::Off)?;
+
+    let text_style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
+
+    let mut line: heapless::String<32> = heapless::String::new();
+    let _ = core::fmt::write(
+        &mut line,
+        format_args!("h={:.1}mm v={:.1}mm/s", snapshot.height_mm, snapshot.velocity_mm_s),
+    );
+    Text::new(&line, Point::new(2, 12), text_style).draw(display)?;
+
+    line.clear();
+    let _ = core::fmt::write(&mut line, format_args!("dac={:.2}V", snapshot.dac_voltage));
+    Text::new(&line, Point::new(2, 24), text_style).draw(display)?;
+
+    // Banner: a large filled rectangle carrying the mode label, inverted
+    // (white label on a black fill instead of black on white) every other
+    // frame while an emergency is latched so it visibly flashes.
+    let banner = Rectangle::new(Point::new(0, 40), Size::new(DISPLAY_WIDTH, 24));
+    let banner_filled = !snapshot.emergency || snapshot.flash_phase;
+
+    let (fill_color, label_color) = if banner_filled {
+        (BinaryColor::On, BinaryColor::Off)
+    } else {
+        (BinaryColor::Off, BinaryColor::On)
+    };
+
+    banner.into_styled(PrimitiveStyle::with_fill(fill_color)).draw(display)?;
+    Text::new(
+        banner_label(snapshot.mode, snapshot.emergency),
+        Point::new(4, 56),
+        MonoTextStyle::new(&FONT_6X10, label_color),
+    )
+    .draw(display)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::mock_display::MockDisplay;
+
+    #[test]
+    fn test_render_does_not_error() {
+        let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+        display.set_allow_out_of_bounds_drawing(true);
+
+        let snapshot = DisplaySnapshot {
+            height_mm: 15.0,
+            velocity_mm_s: 0.0,
+            mode: LevitationMode::Float { height_mm: 15.0 },
+            dac_voltage: 1.5,
+            emergency: false,
+            flash_phase: true,
+        };
+
+        assert!(render(&mut display, &snapshot).is_ok());
+    }
+
+    #[test]
+    fn test_banner_label_reflects_emergency() {
+        assert_eq!(banner_label(LevitationMode::Lifted, false), "STOPPED");
+        assert_eq!(banner_label(LevitationMode::Lifted, true), "FAULT");
+    }
+}
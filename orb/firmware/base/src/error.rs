@@ -16,6 +16,8 @@ pub enum BaseError {
     LevitationUnstable,
     /// Emergency landing triggered
     EmergencyLanding,
+    /// `CalibrationData::from_bytes` CRC mismatch or failed `is_valid()` check
+    CalibrationCorrupt,
 
     // Hardware errors
     /// DAC communication failed
@@ -56,6 +58,7 @@ impl fmt::Display for BaseError {
             Self::InvalidFrequency => write!(f, "Invalid bobble frequency"),
             Self::LevitationUnstable => write!(f, "Levitation unstable"),
             Self::EmergencyLanding => write!(f, "Emergency landing triggered"),
+            Self::CalibrationCorrupt => write!(f, "Calibration data corrupt (CRC or range check failed)"),
             Self::DacError => write!(f, "DAC communication failed"),
             Self::AdcError => write!(f, "ADC read failed"),
             Self::I2cError => write!(f, "I2C bus error"),
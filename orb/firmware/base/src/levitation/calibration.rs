@@ -1,10 +1,26 @@
 //! Calibration data and height-voltage mapping
 //!
 //! Stores the relationship between physical height and sensor/actuator values.
-//! Calibration is performed during manufacturing and stored in EEPROM.
+//! Calibration is performed during manufacturing and stored in EEPROM via
+//! [`CalibrationData::to_bytes`]/[`CalibrationData::from_bytes`], which guard
+//! the record with a trailing CRC-8 so a corrupted or half-written EEPROM
+//! image is rejected at boot instead of producing garbage heights.
+
+use crate::error::BaseError;
+use crate::units::{millimeter, volt, hertz, ElectricPotential, Frequency, Length};
 
 use super::constants;
 
+/// Number of bytes a single serialized `CalibrationPoint` occupies:
+/// `f32` height + `u16` adc + `f32` dac voltage
+const POINT_RECORD_SIZE: usize = 4 + 2 + 4;
+
+/// Size in bytes of a serialized `CalibrationData` record: 5 points, then
+/// `num_points`, `version`, `serial`, the temperature-compensation
+/// coefficients (`t_ref_c`, `adc_tempco`, `dac_tempco`), and a trailing
+/// CRC-8 byte
+pub const RECORD_SIZE: usize = 5 * POINT_RECORD_SIZE + 1 + 4 + 4 + 3 * 4 + 1;
+
 /// Calibration point: height (mm) to ADC/DAC values
 #[derive(Debug, Clone, Copy, Default)]
 pub struct CalibrationPoint {
@@ -27,6 +43,15 @@ pub struct CalibrationData {
 
     /// Unit serial number
     serial: u32,
+
+    /// Coil temperature (°C) the stored points were measured at
+    t_ref_c: f32,
+
+    /// Global Hall-sensor ADC drift coefficient (LSB per °C from `t_ref_c`)
+    adc_tempco: f32,
+
+    /// Global DAC response drift coefficient (V per °C from `t_ref_c`)
+    dac_tempco: f32,
 }
 
 impl Default for CalibrationData {
@@ -43,6 +68,9 @@ impl Default for CalibrationData {
             num_points: 5,
             version: 1,
             serial: 0,
+            t_ref_c: 25.0,
+            adc_tempco: 0.0,
+            dac_tempco: 0.0,
         }
     }
 }
@@ -73,75 +101,103 @@ impl CalibrationData {
         self.serial = serial;
     }
 
+    /// Set the reference temperature the points were measured at and the
+    /// global ADC/DAC drift coefficients used by the `*_compensated` methods
+    pub fn set_tempco(&mut self, t_ref_c: f32, adc_tempco: f32, dac_tempco: f32) {
+        self.t_ref_c = t_ref_c;
+        self.adc_tempco = adc_tempco;
+        self.dac_tempco = dac_tempco;
+    }
+
+    /// Convert ADC reading to height
+    ///
+    /// Thin `uom` wrapper over [`Self::adc_to_height_raw`] for callers
+    /// outside the 100Hz control loop, where the unit safety is worth the
+    /// wrapping cost.
+    pub fn adc_to_height(&self, adc_value: u16) -> Length {
+        Length::new::<millimeter>(self.adc_to_height_raw(adc_value))
+    }
+
     /// Convert ADC reading to height (mm)
     ///
-    /// Uses linear interpolation between calibration points.
-    pub fn adc_to_height(&self, adc_value: u16) -> f32 {
-        // Find surrounding calibration points
+    /// Uses linear interpolation between calibration points. Bare-`f32`
+    /// hot-path helper -- the 100Hz control loop calls this directly to
+    /// avoid `uom` wrapping overhead; use [`Self::adc_to_height`] elsewhere.
+    pub(crate) fn adc_to_height_raw(&self, adc_value: u16) -> f32 {
         // Note: ADC values are typically inversely proportional to height
         // (closer = stronger field = higher ADC)
+        interpolate_height_from_adc(&self.points, self.num_points, adc_value)
+    }
 
-        // Check bounds
-        if adc_value >= self.points[0].adc_value {
-            return self.points[0].height_mm;
-        }
-        if adc_value <= self.points[self.num_points - 1].adc_value {
-            return self.points[self.num_points - 1].height_mm;
-        }
-
-        // Find interval
-        for i in 0..self.num_points - 1 {
-            let p1 = &self.points[i];
-            let p2 = &self.points[i + 1];
-
-            if adc_value <= p1.adc_value && adc_value >= p2.adc_value {
-                // Linear interpolation
-                let t = (p1.adc_value - adc_value) as f32
-                    / (p1.adc_value - p2.adc_value) as f32;
-                return p1.height_mm + t * (p2.height_mm - p1.height_mm);
-            }
-        }
-
-        // Fallback
-        15.0
+    /// Convert ADC reading to height (mm), compensating for coil-temperature
+    /// drift away from the points' reference temperature
+    ///
+    /// Shifts a copy of the stored points by `adc_tempco * (coil_temp_c -
+    /// t_ref_c)` before running the same interpolation as
+    /// [`Self::adc_to_height_raw`], so the height stays accurate as the base
+    /// station warms up under sustained wireless-power load.
+    pub fn adc_to_height_compensated(&self, adc: u16, coil_temp_c: f32) -> f32 {
+        let shifted = self.temp_shifted_points(coil_temp_c);
+        interpolate_height_from_adc(&shifted, self.num_points, adc)
     }
 
-    /// Convert height (mm) to DAC voltage
+    /// Convert height to DAC voltage
     ///
-    /// Uses linear interpolation between calibration points.
-    pub fn height_to_dac(&self, height_mm: f32) -> f32 {
-        // Clamp height to valid range
-        let height = height_mm.clamp(
-            constants::HEIGHT_MIN_MM,
-            constants::HEIGHT_MAX_MM,
-        );
+    /// Thin `uom` wrapper over [`Self::height_to_dac_raw`] for callers
+    /// outside the 100Hz control loop, where the unit safety is worth the
+    /// wrapping cost.
+    pub fn height_to_dac(&self, height: Length) -> ElectricPotential {
+        ElectricPotential::new::<volt>(self.height_to_dac_raw(height.get::<millimeter>()))
+    }
 
-        // Check bounds
-        if height <= self.points[0].height_mm {
-            return self.points[0].dac_voltage;
-        }
-        if height >= self.points[self.num_points - 1].height_mm {
-            return self.points[self.num_points - 1].dac_voltage;
-        }
+    /// Convert height (mm) to DAC voltage (volts)
+    ///
+    /// Uses linear interpolation between calibration points. Bare-`f32`
+    /// hot-path helper -- the 100Hz control loop calls this directly to
+    /// avoid `uom` wrapping overhead; use [`Self::height_to_dac`] elsewhere.
+    pub(crate) fn height_to_dac_raw(&self, height_mm: f32) -> f32 {
+        interpolate_dac_from_height(&self.points, self.num_points, height_mm)
+    }
 
-        // Find interval
-        for i in 0..self.num_points - 1 {
-            let p1 = &self.points[i];
-            let p2 = &self.points[i + 1];
+    /// Convert height (mm) to DAC voltage (volts), compensating for
+    /// coil-temperature drift away from the points' reference temperature
+    ///
+    /// Shifts a copy of the stored points by `dac_tempco * (coil_temp_c -
+    /// t_ref_c)` before running the same interpolation as
+    /// [`Self::height_to_dac_raw`].
+    pub fn height_to_dac_compensated(&self, height_mm: f32, coil_temp_c: f32) -> f32 {
+        let shifted = self.temp_shifted_points(coil_temp_c);
+        interpolate_dac_from_height(&shifted, self.num_points, height_mm)
+    }
 
-            if height >= p1.height_mm && height <= p2.height_mm {
-                // Linear interpolation
-                let t = (height - p1.height_mm) / (p2.height_mm - p1.height_mm);
-                return p1.dac_voltage + t * (p2.dac_voltage - p1.dac_voltage);
-            }
+    /// Copy of `self.points` shifted by the global tempco coefficients at
+    /// `coil_temp_c`, used by the `*_compensated` methods
+    fn temp_shifted_points(&self, coil_temp_c: f32) -> [CalibrationPoint; 5] {
+        let delta_t = coil_temp_c - self.t_ref_c;
+        let adc_shift = self.adc_tempco * delta_t;
+        let dac_shift = self.dac_tempco * delta_t;
+
+        let mut shifted = self.points;
+        for point in &mut shifted {
+            point.adc_value = (point.adc_value as f32 + adc_shift)
+                .round()
+                .clamp(0.0, u16::MAX as f32) as u16;
+            point.dac_voltage += dac_shift;
         }
-
-        // Fallback
-        1.5
+        shifted
     }
 
     /// Convert DAC voltage to approximate height (for verification)
-    pub fn dac_to_height(&self, voltage: f32) -> f32 {
+    ///
+    /// Thin `uom` wrapper over [`Self::dac_to_height_raw`].
+    pub fn dac_to_height(&self, voltage: ElectricPotential) -> Length {
+        Length::new::<millimeter>(self.dac_to_height_raw(voltage.get::<volt>()))
+    }
+
+    /// Convert DAC voltage (volts) to approximate height (mm), for verification
+    ///
+    /// Bare-`f32` helper; see [`Self::dac_to_height`] for the unit-checked version.
+    pub(crate) fn dac_to_height_raw(&self, voltage: f32) -> f32 {
         // Check bounds
         if voltage >= self.points[0].dac_voltage {
             return self.points[0].height_mm;
@@ -210,6 +266,423 @@ impl CalibrationData {
     pub fn serial(&self) -> u32 {
         self.serial
     }
+
+    /// Get the `(t_ref_c, adc_tempco, dac_tempco)` triple set by
+    /// [`Self::set_tempco`], for callers that need to carry it over into a
+    /// freshly fitted record (e.g. [`super::HeightCurveFitter::build`])
+    pub fn tempco(&self) -> (f32, f32, f32) {
+        (self.t_ref_c, self.adc_tempco, self.dac_tempco)
+    }
+
+    /// Convert a filtered ADC reading to height
+    ///
+    /// Thin `uom` wrapper over [`Self::filtered_adc_to_height_raw`] for
+    /// callers outside the 100Hz control loop, where the unit safety is
+    /// worth the wrapping cost.
+    pub fn filtered_adc_to_height<const N: usize>(&self, filter: &AdcFilter<N>) -> Length {
+        Length::new::<millimeter>(self.filtered_adc_to_height_raw(filter))
+    }
+
+    /// Convert a filtered ADC reading to height (mm)
+    ///
+    /// Feeds [`AdcFilter::value`] into [`Self::adc_to_height_raw`], so the
+    /// interpolation sees [`AdcFilter`]'s noise-reduced reading instead of a
+    /// single raw sample. Bare-`f32` hot-path helper; see
+    /// [`Self::filtered_adc_to_height`] for the unit-checked version.
+    pub(crate) fn filtered_adc_to_height_raw<const N: usize>(&self, filter: &AdcFilter<N>) -> f32 {
+        self.adc_to_height_raw(filter.value())
+    }
+
+    /// Convert a filtered ADC reading to height (mm), compensating for
+    /// coil-temperature drift
+    ///
+    /// Feeds [`AdcFilter::value`] into [`Self::adc_to_height_compensated`],
+    /// so the 100Hz control loop gets both noise rejection and temperature
+    /// compensation in one call. Bare-`f32` hot-path helper.
+    pub(crate) fn filtered_adc_to_height_compensated_raw<const N: usize>(
+        &self,
+        filter: &AdcFilter<N>,
+        coil_temp_c: f32,
+    ) -> f32 {
+        self.adc_to_height_compensated(filter.value(), coil_temp_c)
+    }
+
+    /// Serialize to the fixed-size on-EEPROM representation
+    ///
+    /// Packs each point as little-endian (`f32` height, `u16` adc, `f32`
+    /// dac voltage), followed by `num_points`, `version`, `serial`, the
+    /// tempco triple (`t_ref_c`, `adc_tempco`, `dac_tempco`), and a
+    /// trailing CRC-8 (poly 0x07, init 0x00) over everything before it.
+    pub fn to_bytes(&self) -> [u8; RECORD_SIZE] {
+        let mut buf = [0u8; RECORD_SIZE];
+        let mut offset = 0;
+
+        for point in &self.points {
+            buf[offset..offset + 4].copy_from_slice(&point.height_mm.to_le_bytes());
+            offset += 4;
+            buf[offset..offset + 2].copy_from_slice(&point.adc_value.to_le_bytes());
+            offset += 2;
+            buf[offset..offset + 4].copy_from_slice(&point.dac_voltage.to_le_bytes());
+            offset += 4;
+        }
+
+        buf[offset] = self.num_points as u8;
+        offset += 1;
+        buf[offset..offset + 4].copy_from_slice(&self.version.to_le_bytes());
+        offset += 4;
+        buf[offset..offset + 4].copy_from_slice(&self.serial.to_le_bytes());
+        offset += 4;
+        buf[offset..offset + 4].copy_from_slice(&self.t_ref_c.to_le_bytes());
+        offset += 4;
+        buf[offset..offset + 4].copy_from_slice(&self.adc_tempco.to_le_bytes());
+        offset += 4;
+        buf[offset..offset + 4].copy_from_slice(&self.dac_tempco.to_le_bytes());
+        offset += 4;
+
+        buf[offset] = crc8(&buf[..offset]);
+        buf
+    }
+
+    /// Parse a record from bytes
+    ///
+    /// Recomputes the trailing CRC-8 and returns [`BaseError::CalibrationCorrupt`]
+    /// on a mismatch (EEPROM bit-rot or a half-written image) before even
+    /// looking at the payload. Once the bytes are known intact, it still
+    /// runs [`Self::is_valid`] and rejects anything that fails that check,
+    /// so the firmware never flies on a CRC-clean but out-of-range record.
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, BaseError> {
+        if buf.len() != RECORD_SIZE {
+            return Err(BaseError::CalibrationCorrupt);
+        }
+
+        let (payload, crc_byte) = buf.split_at(RECORD_SIZE - 1);
+        if crc8(payload) != crc_byte[0] {
+            return Err(BaseError::CalibrationCorrupt);
+        }
+
+        let mut points = [CalibrationPoint::default(); 5];
+        let mut offset = 0;
+        for point in &mut points {
+            point.height_mm = f32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap());
+            offset += 4;
+            point.adc_value = u16::from_le_bytes(buf[offset..offset + 2].try_into().unwrap());
+            offset += 2;
+            point.dac_voltage = f32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap());
+            offset += 4;
+        }
+
+        let num_points = (buf[offset] as usize).min(5);
+        offset += 1;
+        let version = u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap());
+        offset += 4;
+        let serial = u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap());
+        offset += 4;
+        let t_ref_c = f32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap());
+        offset += 4;
+        let adc_tempco = f32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap());
+        offset += 4;
+        let dac_tempco = f32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap());
+
+        let data = Self { points, num_points, version, serial, t_ref_c, adc_tempco, dac_tempco };
+        if !data.is_valid() {
+            return Err(BaseError::CalibrationCorrupt);
+        }
+
+        Ok(data)
+    }
+}
+
+/// Maximum number of distinct calibration heights `CalibrationBuilder` tracks
+/// for its "at least 3 distinct heights" check -- comfortably above the 5
+/// points a fit ultimately regenerates.
+const MAX_DISTINCT_HEIGHTS: usize = 8;
+
+/// Fits a fresh [`CalibrationData`] from measured samples gathered during a
+/// guided field-calibration procedure
+///
+/// Feed it `(known_height_mm, measured_adc, applied_dac_voltage)` samples as
+/// they're collected, then call [`Self::build`]. Internally this is an
+/// ordinary least-squares fit of `height = m*adc + b` and
+/// `dac_voltage = p*height + q`, run over incrementally accumulated sums so
+/// samples don't need to be buffered -- analogous to the on-device DAC/VREF
+/// calibration routines used by bare-metal thermostat firmware.
+#[derive(Debug, Clone, Copy)]
+pub struct CalibrationBuilder {
+    count: u32,
+    sum_h: f32,
+    sum_adc: f32,
+    sum_dac: f32,
+    sum_h_adc: f32,
+    sum_h_dac: f32,
+    sum_h2: f32,
+    sum_adc2: f32,
+    min_height: f32,
+    max_height: f32,
+    distinct_heights: [f32; MAX_DISTINCT_HEIGHTS],
+    distinct_count: usize,
+}
+
+impl Default for CalibrationBuilder {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            sum_h: 0.0,
+            sum_adc: 0.0,
+            sum_dac: 0.0,
+            sum_h_adc: 0.0,
+            sum_h_dac: 0.0,
+            sum_h2: 0.0,
+            sum_adc2: 0.0,
+            min_height: f32::MAX,
+            max_height: f32::MIN,
+            distinct_heights: [0.0; MAX_DISTINCT_HEIGHTS],
+            distinct_count: 0,
+        }
+    }
+}
+
+impl CalibrationBuilder {
+    /// Create a new, empty sample accumulator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one measured sample at a known, commanded height
+    pub fn push_sample(&mut self, known_height_mm: f32, measured_adc: u16, applied_dac_voltage: f32) {
+        let h = known_height_mm;
+        let adc = measured_adc as f32;
+
+        self.count += 1;
+        self.sum_h += h;
+        self.sum_adc += adc;
+        self.sum_dac += applied_dac_voltage;
+        self.sum_h_adc += h * adc;
+        self.sum_h_dac += h * applied_dac_voltage;
+        self.sum_h2 += h * h;
+        self.sum_adc2 += adc * adc;
+
+        self.min_height = self.min_height.min(h);
+        self.max_height = self.max_height.max(h);
+
+        let is_new = self.distinct_heights[..self.distinct_count]
+            .iter()
+            .all(|&seen| (seen - h).abs() > 0.01);
+        if is_new && self.distinct_count < MAX_DISTINCT_HEIGHTS {
+            self.distinct_heights[self.distinct_count] = h;
+            self.distinct_count += 1;
+        }
+    }
+
+    /// Number of samples collected so far
+    pub fn sample_count(&self) -> u32 {
+        self.count
+    }
+
+    /// Fit the accumulated samples into a fresh `CalibrationData`
+    ///
+    /// `version` is bumped past `previous.version()` and `serial` is carried
+    /// over unchanged, so the fitted record still identifies the same unit.
+    /// Returns [`BaseError::AdcError`] if fewer than 3 distinct heights were
+    /// sampled, either regression's denominator is degenerate (e.g. every
+    /// sample landed at the same ADC reading), or the fitted curve fails
+    /// [`CalibrationData::is_valid`].
+    pub fn build(&self, previous: &CalibrationData) -> Result<CalibrationData, BaseError> {
+        if self.distinct_count < 3 {
+            return Err(BaseError::AdcError);
+        }
+
+        let n = self.count as f32;
+
+        // height = m*adc + b
+        let denom_adc = n * self.sum_adc2 - self.sum_adc * self.sum_adc;
+        if denom_adc.abs() <= f32::EPSILON {
+            return Err(BaseError::AdcError);
+        }
+        let m = (n * self.sum_h_adc - self.sum_h * self.sum_adc) / denom_adc;
+        let b = (self.sum_h - m * self.sum_adc) / n;
+
+        // dac_voltage = p*height + q
+        let denom_h = n * self.sum_h2 - self.sum_h * self.sum_h;
+        if denom_h.abs() <= f32::EPSILON {
+            return Err(BaseError::AdcError);
+        }
+        let p = (n * self.sum_h_dac - self.sum_h * self.sum_dac) / denom_h;
+        let q = (self.sum_dac - p * self.sum_h) / n;
+
+        // Regenerate 5 evenly-spaced points spanning the observed height range
+        let mut points = [CalibrationPoint::default(); 5];
+        for (i, point) in points.iter_mut().enumerate() {
+            let height = self.min_height
+                + (self.max_height - self.min_height) * (i as f32 / 4.0);
+            let adc = ((height - b) / m).round().clamp(0.0, u16::MAX as f32) as u16;
+            point.height_mm = height;
+            point.adc_value = adc;
+            point.dac_voltage = p * height + q;
+        }
+
+        let mut data = CalibrationData::from_points(&points);
+        data.set_version(previous.version().wrapping_add(1));
+        data.set_serial(previous.serial());
+        data.set_tempco(previous.t_ref_c, previous.adc_tempco, previous.dac_tempco);
+
+        if !data.is_valid() {
+            return Err(BaseError::AdcError);
+        }
+
+        Ok(data)
+    }
+}
+
+/// Noise-reduction mode for [`AdcFilter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdcFilterMode {
+    /// Arithmetic mean over the window -- cheap, but a single large spike
+    /// still drags the average toward it.
+    MovingAverage,
+    /// Median over the window -- rejects a single-sample spike (e.g. EMI
+    /// near the WPT coil) that a mean would smear across several readings.
+    Median,
+}
+
+/// Fixed-capacity circular buffer that smooths noisy Hall-sensor ADC reads
+/// before they reach [`CalibrationData::adc_to_height`]
+///
+/// `N` is the averaging window (up to 16 is the expected range). The buffer
+/// must fill once before [`Self::value`] reflects the configured mode --
+/// until then it returns the most recent raw sample pushed.
+#[derive(Debug, Clone, Copy)]
+pub struct AdcFilter<const N: usize> {
+    samples: [u16; N],
+    index: usize,
+    filled: bool,
+    mode: AdcFilterMode,
+}
+
+impl<const N: usize> AdcFilter<N> {
+    /// Create a new, empty filter in the given mode
+    pub fn new(mode: AdcFilterMode) -> Self {
+        Self { samples: [0; N], index: 0, filled: false, mode }
+    }
+
+    /// Push one raw ADC sample into the ring
+    pub fn push(&mut self, sample: u16) {
+        self.samples[self.index] = sample;
+        self.index = (self.index + 1) % N;
+        if self.index == 0 {
+            self.filled = true;
+        }
+    }
+
+    /// True once `N` samples have been pushed and `value()` reflects the
+    /// configured filter mode rather than a raw passthrough
+    pub fn is_filled(&self) -> bool {
+        self.filled
+    }
+
+    /// The filtered reading
+    ///
+    /// Returns the most recently pushed raw sample until the buffer has
+    /// filled once; a partial window isn't enough to trust the mean or
+    /// median yet.
+    pub fn value(&self) -> u16 {
+        if !self.filled {
+            let last = (self.index + N - 1) % N;
+            return self.samples[last];
+        }
+
+        match self.mode {
+            AdcFilterMode::MovingAverage => {
+                let sum: u32 = self.samples.iter().map(|&s| s as u32).sum();
+                (sum / N as u32) as u16
+            }
+            AdcFilterMode::Median => {
+                let mut sorted = self.samples;
+                sorted.sort_unstable();
+                sorted[N / 2]
+            }
+        }
+    }
+}
+
+/// Interpolate height from an ADC reading over an arbitrary ordered point
+/// set -- shared by [`CalibrationData::adc_to_height_raw`] and
+/// [`CalibrationData::adc_to_height_compensated`], which interpolates over
+/// temperature-shifted points instead of `self.points` directly.
+fn interpolate_height_from_adc(points: &[CalibrationPoint], num_points: usize, adc_value: u16) -> f32 {
+    // Note: ADC values are typically inversely proportional to height
+    // (closer = stronger field = higher ADC)
+
+    // Check bounds
+    if adc_value >= points[0].adc_value {
+        return points[0].height_mm;
+    }
+    if adc_value <= points[num_points - 1].adc_value {
+        return points[num_points - 1].height_mm;
+    }
+
+    // Find interval
+    for i in 0..num_points - 1 {
+        let p1 = &points[i];
+        let p2 = &points[i + 1];
+
+        if adc_value <= p1.adc_value && adc_value >= p2.adc_value {
+            // Linear interpolation
+            let t = (p1.adc_value - adc_value) as f32 / (p1.adc_value - p2.adc_value) as f32;
+            return p1.height_mm + t * (p2.height_mm - p1.height_mm);
+        }
+    }
+
+    // Fallback
+    15.0
+}
+
+/// Interpolate DAC voltage from a height over an arbitrary ordered point
+/// set -- shared by [`CalibrationData::height_to_dac_raw`] and
+/// [`CalibrationData::height_to_dac_compensated`], which interpolates over
+/// temperature-shifted points instead of `self.points` directly.
+fn interpolate_dac_from_height(points: &[CalibrationPoint], num_points: usize, height_mm: f32) -> f32 {
+    // Clamp height to valid range
+    let height = height_mm.clamp(constants::HEIGHT_MIN_MM, constants::HEIGHT_MAX_MM);
+
+    // Check bounds
+    if height <= points[0].height_mm {
+        return points[0].dac_voltage;
+    }
+    if height >= points[num_points - 1].height_mm {
+        return points[num_points - 1].dac_voltage;
+    }
+
+    // Find interval
+    for i in 0..num_points - 1 {
+        let p1 = &points[i];
+        let p2 = &points[i + 1];
+
+        if height >= p1.height_mm && height <= p2.height_mm {
+            // Linear interpolation
+            let t = (height - p1.height_mm) / (p2.height_mm - p1.height_mm);
+            return p1.dac_voltage + t * (p2.dac_voltage - p1.dac_voltage);
+        }
+    }
+
+    // Fallback
+    1.5
+}
+
+/// CRC-8 (polynomial 0x07, init 0x00) -- the record is tiny, so a bitwise
+/// implementation is cheaper than a lookup table in flash.
+fn crc8(data: &[u8]) -> u8 {
+    let mut crc: u8 = 0x00;
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            if crc & 0x80 != 0 {
+                crc = (crc << 1) ^ 0x07;
+            } else {
+                crc <<= 1;
+            }
+        }
+    }
+    crc
 }
 
 /// WPT frequency calibration data
@@ -241,7 +714,20 @@ impl WptCalibrationData {
     }
 
     /// Get optimal frequency for given height
-    pub fn optimal_frequency(&self, height_mm: f32) -> f32 {
+    ///
+    /// Thin `uom` wrapper over [`Self::optimal_frequency_raw`] for callers
+    /// outside the 100Hz control loop, where the unit safety is worth the
+    /// wrapping cost.
+    pub fn optimal_frequency(&self, height: Length) -> Frequency {
+        Frequency::new::<hertz>(self.optimal_frequency_raw(height.get::<millimeter>()))
+    }
+
+    /// Get optimal frequency (Hz) for given height (mm)
+    ///
+    /// Bare-`f32` hot-path helper -- the 100Hz control loop calls this
+    /// directly to avoid `uom` wrapping overhead; use
+    /// [`Self::optimal_frequency`] elsewhere.
+    pub(crate) fn optimal_frequency_raw(&self, height_mm: f32) -> f32 {
         // Clamp height
         let height = height_mm.clamp(5.0, 25.0);
 
@@ -281,12 +767,12 @@ mod tests {
         let cal = CalibrationData::default();
 
         // Exact calibration points
-        assert!((cal.height_to_dac(5.0) - 2.5).abs() < 0.01);
-        assert!((cal.height_to_dac(15.0) - 1.5).abs() < 0.01);
-        assert!((cal.height_to_dac(25.0) - 0.5).abs() < 0.01);
+        assert!((cal.height_to_dac_raw(5.0) - 2.5).abs() < 0.01);
+        assert!((cal.height_to_dac_raw(15.0) - 1.5).abs() < 0.01);
+        assert!((cal.height_to_dac_raw(25.0) - 0.5).abs() < 0.01);
 
         // Interpolated values
-        let v_12 = cal.height_to_dac(12.5);
+        let v_12 = cal.height_to_dac_raw(12.5);
         assert!(v_12 > 1.5 && v_12 < 2.0);
     }
 
@@ -295,15 +781,29 @@ mod tests {
         let cal = CalibrationData::default();
 
         // Exact calibration points
-        assert!((cal.adc_to_height(3800) - 5.0).abs() < 0.1);
-        assert!((cal.adc_to_height(2600) - 15.0).abs() < 0.1);
-        assert!((cal.adc_to_height(1400) - 25.0).abs() < 0.1);
+        assert!((cal.adc_to_height_raw(3800) - 5.0).abs() < 0.1);
+        assert!((cal.adc_to_height_raw(2600) - 15.0).abs() < 0.1);
+        assert!((cal.adc_to_height_raw(1400) - 25.0).abs() < 0.1);
 
         // Interpolated values
-        let h = cal.adc_to_height(2900);
+        let h = cal.adc_to_height_raw(2900);
         assert!(h > 12.0 && h < 15.0);
     }
 
+    #[test]
+    fn test_height_to_dac_uom_wrapper_matches_raw() {
+        let cal = CalibrationData::default();
+
+        let dac = cal.height_to_dac(Length::new::<millimeter>(12.5));
+        assert!((dac.get::<volt>() - cal.height_to_dac_raw(12.5)).abs() < 0.01);
+
+        let height = cal.adc_to_height(2900);
+        assert!((height.get::<millimeter>() - cal.adc_to_height_raw(2900)).abs() < 0.01);
+
+        let back = cal.dac_to_height(dac);
+        assert!((back.get::<millimeter>() - cal.dac_to_height_raw(dac.get::<volt>())).abs() < 0.01);
+    }
+
     #[test]
     fn test_calibration_validation() {
         let cal = CalibrationData::default();
@@ -315,15 +815,86 @@ mod tests {
         assert!(!bad_cal.is_valid());
     }
 
+    #[test]
+    fn test_calibration_roundtrips_through_bytes() {
+        let mut cal = CalibrationData::default();
+        cal.set_version(3);
+        cal.set_serial(42);
+
+        let bytes = cal.to_bytes();
+        let parsed = CalibrationData::from_bytes(&bytes).unwrap();
+
+        assert_eq!(parsed.num_points, cal.num_points);
+        assert_eq!(parsed.version(), 3);
+        assert_eq!(parsed.serial(), 42);
+        assert!((parsed.height_to_dac_raw(15.0) - cal.height_to_dac_raw(15.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_corrupted_bytes_rejected() {
+        let cal = CalibrationData::default();
+        let mut bytes = cal.to_bytes();
+        bytes[0] ^= 0xFF; // flip every bit of the first point's height's low byte
+
+        assert!(matches!(CalibrationData::from_bytes(&bytes), Err(BaseError::CalibrationCorrupt)));
+    }
+
+    #[test]
+    fn test_wrong_length_bytes_rejected() {
+        let cal = CalibrationData::default();
+        let bytes = cal.to_bytes();
+
+        assert!(matches!(CalibrationData::from_bytes(&bytes[..RECORD_SIZE - 1]), Err(BaseError::CalibrationCorrupt)));
+    }
+
+    #[test]
+    fn test_calibration_builder_fits_default_points() {
+        let mut builder = CalibrationBuilder::new();
+        for p in CalibrationData::default().points {
+            builder.push_sample(p.height_mm, p.adc_value, p.dac_voltage);
+        }
+
+        let previous = CalibrationData::default();
+        let fitted = builder.build(&previous).unwrap();
+
+        assert!(fitted.is_valid());
+        assert_eq!(fitted.version(), previous.version() + 1);
+        assert_eq!(fitted.serial(), previous.serial());
+        assert!((fitted.adc_to_height_raw(3800) - 5.0).abs() < 0.1);
+        assert!((fitted.height_to_dac_raw(15.0) - 1.5).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_calibration_builder_rejects_too_few_distinct_heights() {
+        let mut builder = CalibrationBuilder::new();
+        builder.push_sample(10.0, 3200, 2.0);
+        builder.push_sample(10.0, 3210, 2.0);
+        builder.push_sample(20.0, 2000, 1.0);
+
+        assert!(matches!(builder.build(&CalibrationData::default()), Err(BaseError::AdcError)));
+    }
+
+    #[test]
+    fn test_calibration_builder_rejects_degenerate_adc_fit() {
+        let mut builder = CalibrationBuilder::new();
+        // Same ADC reading at every height -- height can't be fit as a
+        // function of adc, so the regression denominator is zero.
+        builder.push_sample(5.0, 3000, 2.5);
+        builder.push_sample(15.0, 3000, 1.5);
+        builder.push_sample(25.0, 3000, 0.5);
+
+        assert!(matches!(builder.build(&CalibrationData::default()), Err(BaseError::AdcError)));
+    }
+
     #[test]
     fn test_wpt_calibration() {
         let wpt = WptCalibrationData::default();
 
         // Check interpolation
-        let f_5 = wpt.optimal_frequency(5.0);
+        let f_5 = wpt.optimal_frequency_raw(5.0);
         assert!((f_5 - 132_000.0).abs() < 100.0);
 
-        let f_12 = wpt.optimal_frequency(12.5);
+        let f_12 = wpt.optimal_frequency_raw(12.5);
         assert!(f_12 > 136_000.0 && f_12 < 138_000.0);
 
         // Efficiency decreases with height
@@ -331,4 +902,108 @@ mod tests {
         let eff_20 = wpt.expected_efficiency(20.0);
         assert!(eff_5 > eff_20);
     }
+
+    #[test]
+    fn test_wpt_optimal_frequency_uom_wrapper_matches_raw() {
+        let wpt = WptCalibrationData::default();
+
+        let f = wpt.optimal_frequency(Length::new::<millimeter>(12.5));
+        assert!((f.get::<hertz>() - wpt.optimal_frequency_raw(12.5)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_adc_filter_returns_raw_sample_until_filled() {
+        let mut filter: AdcFilter<4> = AdcFilter::new(AdcFilterMode::MovingAverage);
+        assert!(!filter.is_filled());
+
+        filter.push(100);
+        assert_eq!(filter.value(), 100);
+        filter.push(200);
+        assert_eq!(filter.value(), 200);
+        assert!(!filter.is_filled());
+    }
+
+    #[test]
+    fn test_adc_filter_moving_average() {
+        let mut filter: AdcFilter<4> = AdcFilter::new(AdcFilterMode::MovingAverage);
+        for sample in [100, 200, 300, 400] {
+            filter.push(sample);
+        }
+
+        assert!(filter.is_filled());
+        assert_eq!(filter.value(), 250);
+    }
+
+    #[test]
+    fn test_adc_filter_median_rejects_spike() {
+        let mut filter: AdcFilter<5> = AdcFilter::new(AdcFilterMode::Median);
+        for sample in [3800, 3790, 3810, 3795, 9999] {
+            filter.push(sample);
+        }
+
+        // A lone EMI spike shouldn't move the median, unlike the mean.
+        assert_eq!(filter.value(), 3800);
+    }
+
+    #[test]
+    fn test_filtered_adc_to_height_uses_filter_value() {
+        let cal = CalibrationData::default();
+        let mut filter: AdcFilter<4> = AdcFilter::new(AdcFilterMode::MovingAverage);
+        for _ in 0..4 {
+            filter.push(3800);
+        }
+
+        let height = cal.filtered_adc_to_height(&filter);
+        assert!((height.get::<millimeter>() - cal.adc_to_height_raw(3800)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_calibration_roundtrips_tempco_through_bytes() {
+        let mut cal = CalibrationData::default();
+        cal.set_tempco(20.0, -1.5, 0.002);
+
+        let bytes = cal.to_bytes();
+        let parsed = CalibrationData::from_bytes(&bytes).unwrap();
+
+        // Compare away from t_ref_c, where the ADC/DAC coefficients
+        // actually shift the points
+        assert!((parsed.adc_to_height_compensated(3200, 45.0) - cal.adc_to_height_compensated(3200, 45.0)).abs() < 0.01);
+        assert!((parsed.height_to_dac_compensated(15.0, 45.0) - cal.height_to_dac_compensated(15.0, 45.0)).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_zero_tempco_compensation_matches_uncompensated() {
+        let cal = CalibrationData::default();
+
+        // Default tempco coefficients are all zero, so compensation at any
+        // coil temperature should be a no-op.
+        let raw = cal.adc_to_height_raw(3200);
+        let compensated = cal.adc_to_height_compensated(3200, 50.0);
+        assert!((raw - compensated).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_adc_tempco_shifts_height_reading() {
+        let mut cal = CalibrationData::default();
+        // ADC drifts down 1 count per degree above the 25C reference.
+        cal.set_tempco(25.0, -1.0, 0.0);
+
+        let at_ref = cal.adc_to_height_compensated(3200, 25.0);
+        let hot = cal.adc_to_height_compensated(3200, 75.0);
+
+        // Same raw ADC reading at a hotter coil implies the curve shifted,
+        // so the decoded height should differ from the reference-temp case.
+        assert!((at_ref - hot).abs() > 0.01);
+    }
+
+    #[test]
+    fn test_dac_tempco_shifts_setpoint() {
+        let mut cal = CalibrationData::default();
+        cal.set_tempco(25.0, 0.0, 0.01);
+
+        let at_ref = cal.height_to_dac_compensated(15.0, 25.0);
+        let hot = cal.height_to_dac_compensated(15.0, 75.0);
+
+        assert!((hot - at_ref - 0.5).abs() < 0.01);
+    }
 }
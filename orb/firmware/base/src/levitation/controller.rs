@@ -6,8 +6,8 @@
 use super::{
     LevitationMode, LevitationModeSimple, LevitationState,
     trajectory::{HeightTrajectory, BobbleAnimation, HeightMotionGenerator},
-    safety::{LevitationSafetyVerifier, SafetyInterlockManager, SafetyResult},
-    calibration::{CalibrationData, WptCalibrationData},
+    safety::{LevitationSafetyVerifier, SafetyInterlockManager, SafetyResult, EMERGENCY_FLAG},
+    calibration::{CalibrationData, WptCalibrationData, AdcFilter, AdcFilterMode},
     constants,
 };
 use crate::error::{BaseError, BaseResult};
@@ -36,13 +36,25 @@ pub struct HeightController {
     last_adc_value: u16,
     last_dac_voltage: f32,
 
-    // Velocity estimation (for safety checks)
-    velocity_filter: VelocityFilter,
+    // Height/velocity estimation (for safety checks)
+    velocity_filter: ComplementaryVelocityFilter,
 
     // Oscillation detection
     oscillation_detector: OscillationDetector,
+
+    // Hall-sensor ADC noise filtering
+    adc_filter: AdcFilter<ADC_FILTER_WINDOW>,
+
+    // Closed-loop descent-rate control during `Landing`
+    descent_regulator: DescentRateRegulator,
+
+    // Final vertical-speed/acceleration saturation on the commanded setpoint
+    output_limiter: OutputSlewLimiter,
 }
 
+/// Window size for the controller's [`AdcFilter`]
+const ADC_FILTER_WINDOW: usize = 8;
+
 impl HeightController {
     /// Create a new height controller with default calibration
     pub fn new() -> Self {
@@ -56,8 +68,12 @@ impl HeightController {
             interlock: SafetyInterlockManager::new(),
             last_adc_value: 0,
             last_dac_voltage: 1.5,
-            velocity_filter: VelocityFilter::new(),
+            velocity_filter: ComplementaryVelocityFilter::new(),
             oscillation_detector: OscillationDetector::new(),
+            // Median mode rejects single-sample EMI spikes near the WPT coil
+            adc_filter: AdcFilter::new(AdcFilterMode::Median),
+            descent_regulator: DescentRateRegulator::new(),
+            output_limiter: OutputSlewLimiter::new(),
         }
     }
 
@@ -73,11 +89,27 @@ impl HeightController {
     pub fn update(&mut self, adc_value: u16, power_ok: bool, coil_temp: f32) -> BaseResult<(f32, f32)> {
         let dt = 1.0 / constants::CONTROL_RATE_HZ as f32;
 
-        // Convert ADC to height
-        let height_mm = self.height_cal.adc_to_height(adc_value);
+        // Filter the raw ADC reading before converting to height, then use
+        // the bare-`f32`, temperature-compensated hot-path helper rather
+        // than the `uom`-wrapped public API, since this runs every 100Hz
+        // tick and the coil warms up under sustained wireless-power load
+        self.adc_filter.push(adc_value);
+        let height_mm = self
+            .height_cal
+            .filtered_adc_to_height_compensated_raw(&self.adc_filter, coil_temp);
+
+        // Approximate modeled acceleration from last tick's commanded DAC
+        // voltage relative to the adaptively-estimated neutral (hover)
+        // voltage -- a coarse commanded-force-vs-gravity model, pending a
+        // real accelerometer. DAC voltage is inversely related to commanded
+        // height (see `CalibrationData::height_to_dac_raw`), so a voltage
+        // above neutral commands a *lower* height -- a negative (downward)
+        // acceleration.
+        let modeled_accel_mm_s2 = (self.descent_regulator.neutral_voltage() - self.last_dac_voltage)
+            * constants::DAC_ACCEL_GAIN_MM_S2_PER_V;
 
         // Update velocity estimate
-        self.velocity_filter.update(height_mm, dt);
+        self.velocity_filter.update(height_mm, modeled_accel_mm_s2, dt);
         let velocity = self.velocity_filter.velocity();
 
         // Update oscillation detection
@@ -86,6 +118,7 @@ impl HeightController {
 
         // Update state
         self.state.height_mm = height_mm;
+        self.state.height_estimate_mm = self.velocity_filter.height_estimate();
         self.state.velocity_mm_s = velocity;
         self.state.oscillation_amplitude_mm = oscillation;
         self.state.electromagnet_temp_c = coil_temp;
@@ -103,6 +136,27 @@ impl HeightController {
             return Ok((0.0, 0.0)); // Let gravity + eddy damping do the work
         }
 
+        // An interrupt-latched hardware fault (over-temperature, WPT FOD)
+        // overrides the normal trajectory immediately -- it reacts faster
+        // than the 10Hz periodic barrier-function check can. Stay latched
+        // in Landing until `EMERGENCY_FLAG::clear()` is called, so the
+        // operator must explicitly acknowledge the fault before re-levitating.
+        if EMERGENCY_FLAG.is_set() && !self.interlock.is_emergency() {
+            if !self.motion.is_active() {
+                let trajectory = HeightTrajectory::new(
+                    self.state.height_mm,
+                    constants::HEIGHT_CHARGE_MM,
+                    0.5, // fast fixed-duration smoothstep descent (not jerk-limited)
+                );
+                self.motion.start_trajectory(trajectory);
+                self.descent_regulator.reset();
+            }
+            self.mode = LevitationMode::Landing {
+                current_height_mm: self.state.height_mm,
+                descent_rate_mm_s: constants::MAX_DESCENT_RATE_MM_S,
+            };
+        }
+
         // Get target height from motion generator
         let (target_height, _target_velocity) = self.motion.update(dt);
 
@@ -117,11 +171,32 @@ impl HeightController {
             target_height
         };
 
-        // Convert to DAC voltage
-        let dac_voltage = self.height_cal.height_to_dac(corrected_height);
+        // Enforce vertical speed/acceleration bounds on the final setpoint
+        // (see `OutputSlewLimiter`) -- covers every path into
+        // `corrected_height`, including the `corrective_action` spike
+        // above. Outside `Landing` the DAC voltage is derived from this
+        // limited setpoint; during `Landing` it comes from
+        // `DescentRateRegulator` below instead, which this limiter does
+        // not bound
+        let limited_height = self.output_limiter.update(corrected_height, dt);
+
+        // During a controlled landing, close the loop on measured descent
+        // rate instead of the open-loop height->DAC curve: the curve alone
+        // doesn't account for coil-temperature/supply-voltage drift in
+        // lift-per-volt, which otherwise makes the actual descent rate
+        // wander away from `descent_rate_mm_s` as the magnet heats up.
+        let dac_voltage = if let LevitationMode::Landing { descent_rate_mm_s, .. } = self.mode {
+            self.mode = LevitationMode::Landing {
+                current_height_mm: limited_height,
+                descent_rate_mm_s,
+            };
+            self.descent_regulator.update(velocity, descent_rate_mm_s)
+        } else {
+            self.height_cal.height_to_dac_compensated(limited_height, coil_temp)
+        };
 
-        // Get optimal WPT frequency
-        let wpt_freq = self.wpt_cal.optimal_frequency(corrected_height);
+        // Get optimal WPT frequency (hot-path helper, see above)
+        let wpt_freq = self.wpt_cal.optimal_frequency_raw(limited_height);
 
         // Store for next iteration
         self.last_adc_value = adc_value;
@@ -136,8 +211,7 @@ impl HeightController {
             return Err(BaseError::EmergencyLanding);
         }
 
-        let trajectory = HeightTrajectory::to_charging(self.state.height_mm);
-        self.motion.start_trajectory(trajectory);
+        self.motion.start_reference(self.state.height_mm, constants::HEIGHT_CHARGE_MM);
 
         self.mode = LevitationMode::Charging {
             target_height_mm: constants::HEIGHT_CHARGE_MM,
@@ -153,8 +227,7 @@ impl HeightController {
             return Err(BaseError::EmergencyLanding);
         }
 
-        let trajectory = HeightTrajectory::to_float(self.state.height_mm);
-        self.motion.start_trajectory(trajectory);
+        self.motion.start_reference(self.state.height_mm, constants::HEIGHT_FLOAT_MM);
 
         self.mode = LevitationMode::Float {
             height_mm: constants::HEIGHT_FLOAT_MM,
@@ -164,7 +237,13 @@ impl HeightController {
     }
 
     /// Command: Set specific height
-    pub fn set_height(&mut self, target_mm: f32, duration_ms: u32) -> BaseResult<()> {
+    ///
+    /// `duration_ms` is accepted for API compatibility but otherwise
+    /// ignored: the transition is filtered through a
+    /// [`super::ReferenceModel`], whose own rate/acceleration bounds (not
+    /// the caller's requested duration) now govern how fast the height
+    /// actually moves.
+    pub fn set_height(&mut self, target_mm: f32, _duration_ms: u32) -> BaseResult<()> {
         if self.interlock.is_emergency() {
             return Err(BaseError::EmergencyLanding);
         }
@@ -173,9 +252,7 @@ impl HeightController {
             return Err(BaseError::HeightOutOfRange);
         }
 
-        let duration_s = duration_ms as f32 / 1000.0;
-        let trajectory = HeightTrajectory::new(self.state.height_mm, target_mm, duration_s);
-        self.motion.start_trajectory(trajectory);
+        self.motion.start_reference(self.state.height_mm, target_mm);
 
         self.mode = LevitationMode::Float { height_mm: target_mm };
 
@@ -227,12 +304,8 @@ impl HeightController {
 
     /// Command: Controlled landing
     pub fn land(&mut self) -> BaseResult<()> {
-        let trajectory = HeightTrajectory::new(
-            self.state.height_mm,
-            constants::HEIGHT_MIN_MM,
-            3.0, // 3 second gentle landing
-        );
-        self.motion.start_trajectory(trajectory);
+        self.motion.start_reference(self.state.height_mm, constants::HEIGHT_MIN_MM);
+        self.descent_regulator.reset();
 
         self.mode = LevitationMode::Landing {
             current_height_mm: self.state.height_mm,
@@ -251,6 +324,7 @@ impl HeightController {
     /// Reset after manual intervention
     pub fn reset(&mut self) -> BaseResult<()> {
         self.interlock.reset();
+        EMERGENCY_FLAG.clear();
         self.motion.stop(self.state.height_mm);
         self.mode = LevitationMode::Float {
             height_mm: constants::HEIGHT_FLOAT_MM,
@@ -278,6 +352,15 @@ impl HeightController {
         self.interlock.is_emergency()
     }
 
+    /// Current adaptively-estimated hover (neutral) DAC voltage
+    ///
+    /// Tracks coil-temperature/supply-voltage drift in lift-per-volt; use
+    /// as a feed-forward starting point for a future landing or hover
+    /// rather than a fixed calibration-curve value.
+    pub fn neutral_dac_voltage(&self) -> f32 {
+        self.descent_regulator.neutral_voltage()
+    }
+
     /// Notify that orb has been lifted off base
     pub fn on_orb_lifted(&mut self) {
         self.mode = LevitationMode::Lifted;
@@ -290,12 +373,7 @@ impl HeightController {
             self.mode = LevitationMode::Float {
                 height_mm: constants::HEIGHT_FLOAT_MM,
             };
-            let trajectory = HeightTrajectory::new(
-                constants::HEIGHT_MIN_MM,
-                constants::HEIGHT_FLOAT_MM,
-                1.5,
-            );
-            self.motion.start_trajectory(trajectory);
+            self.motion.start_reference(constants::HEIGHT_MIN_MM, constants::HEIGHT_FLOAT_MM);
         }
     }
 
@@ -314,33 +392,181 @@ impl Default for HeightController {
     }
 }
 
-/// Simple velocity estimator using finite difference with filtering
-struct VelocityFilter {
-    last_height: f32,
-    velocity: f32,
-    alpha: f32, // Low-pass filter coefficient
+/// Third-order complementary filter estimating height and velocity
+///
+/// Replaces a plain finite-difference + EMA, which lags and amplifies Hall
+/// ADC quantization noise. Fuses the measured height with a modeled vertical
+/// acceleration (net commanded-DAC-force vs. gravity -- see
+/// `constants::DAC_ACCEL_GAIN_MM_S2_PER_V` -- until a real accelerometer is
+/// wired up) through three integrators:
+///
+/// - `integ1` absorbs acceleration bias
+/// - `integ2` is the filtered velocity output ([`Self::velocity`])
+/// - `integ3` tracks height ([`Self::height_estimate`]); its disagreement
+///   with the measurement (`err`) corrects all three each tick
+///
+/// `K1`/`K2`/`K3` set the complementary crossover -- trusting the
+/// accel/model at high frequency, the Hall height at low frequency -- and
+/// are a triple real pole at `CORNER_RAD_S` (`K1=3*wc, K2=3*wc^2, K3=wc^3`).
+struct ComplementaryVelocityFilter {
+    integ1: f32,
+    integ2: f32,
+    integ3: f32,
 }
 
-impl VelocityFilter {
+impl ComplementaryVelocityFilter {
+    /// Complementary crossover frequency (rad/s)
+    const CORNER_RAD_S: f32 = 2.0;
+    const K1: f32 = 3.0 * Self::CORNER_RAD_S;
+    const K2: f32 = 3.0 * Self::CORNER_RAD_S * Self::CORNER_RAD_S;
+    const K3: f32 = Self::CORNER_RAD_S * Self::CORNER_RAD_S * Self::CORNER_RAD_S;
+
+    fn new() -> Self {
+        Self { integ1: 0.0, integ2: 0.0, integ3: 0.0 }
+    }
+
+    /// Fuse a new height measurement (mm) and modeled/measured vertical
+    /// acceleration (mm/s^2)
+    fn update(&mut self, height_meas: f32, accel_mm_s2: f32, dt: f32) {
+        let err = height_meas - self.integ3;
+        self.integ1 += Self::K3 * err * dt;
+        self.integ2 += (self.integ1 + accel_mm_s2 + Self::K2 * err) * dt;
+        self.integ3 += (self.integ2 + Self::K1 * err) * dt;
+    }
+
+    fn velocity(&self) -> f32 {
+        self.integ2
+    }
+
+    /// Filtered height estimate (mm) -- smoother than the raw ADC-derived
+    /// reading, at the cost of the filter's own lag
+    fn height_estimate(&self) -> f32 {
+        self.integ3
+    }
+}
+
+/// Closed-loop descent-rate regulator for [`LevitationMode::Landing`]
+///
+/// Trims the commanded DAC voltage each tick to hold the measured descent
+/// rate at a target, while continuously re-estimating the "neutral" (hover)
+/// DAC voltage -- the voltage that would produce zero velocity right now.
+/// Coil resistance rises with temperature and lift-per-volt sags with
+/// supply voltage, so a fixed open-loop hover voltage drifts over the
+/// course of a landing; this regulator adapts by averaging descent rate
+/// and commanded voltage over a sliding window and solving for the
+/// neutral point from their ratio, so subsequent ticks (and the next
+/// landing, via [`HeightController::neutral_dac_voltage`]) start from a
+/// calibrated estimate instead of a guess.
+struct DescentRateRegulator {
+    sum_descent_rate_mm_s: f32,
+    sum_dac_v: f32,
+    sample_count: u32,
+    calculated_neutral_v: f32,
+}
+
+impl DescentRateRegulator {
+    /// Proportional gain trimming DAC voltage per mm/s of descent-rate error
+    const KP: f32 = 0.02;
+    /// Sliding observation window, in control ticks (0.5s at 100Hz)
+    const WINDOW_TICKS: u32 = 50;
+
     fn new() -> Self {
         Self {
-            last_height: 0.0,
-            velocity: 0.0,
-            alpha: 0.3, // More responsive
+            sum_descent_rate_mm_s: 0.0,
+            sum_dac_v: 0.0,
+            sample_count: 0,
+            // Roughly mid-curve; refined once the first window completes
+            calculated_neutral_v: (constants::DAC_V_AT_5MM + constants::DAC_V_AT_25MM) / 2.0,
         }
     }
 
-    fn update(&mut self, height: f32, dt: f32) {
-        if dt > 0.0 {
-            let raw_velocity = (height - self.last_height) / dt;
-            // Exponential moving average filter
-            self.velocity = self.alpha * raw_velocity + (1.0 - self.alpha) * self.velocity;
-            self.last_height = height;
+    /// Clear the accumulated window, e.g. on entering a new landing
+    fn reset(&mut self) {
+        self.sum_descent_rate_mm_s = 0.0;
+        self.sum_dac_v = 0.0;
+        self.sample_count = 0;
+    }
+
+    /// Compute this tick's trimmed DAC voltage and fold it into the
+    /// neutral-voltage estimate
+    ///
+    /// `velocity_mm_s` is the measured vertical velocity (positive =
+    /// rising), `target_descent_rate_mm_s` the desired (positive) descent
+    /// rate.
+    fn update(&mut self, velocity_mm_s: f32, target_descent_rate_mm_s: f32) -> f32 {
+        let descent_rate_mm_s = -velocity_mm_s;
+        let error = target_descent_rate_mm_s - descent_rate_mm_s;
+        // DAC voltage is inversely related to commanded height (see
+        // `CalibrationData::height_to_dac_raw`), so descending too slowly
+        // (error > 0) needs *more* voltage, not less
+        let dac_v = (self.calculated_neutral_v + Self::KP * error)
+            .clamp(constants::DAC_V_AT_25MM, constants::DAC_V_AT_5MM);
+
+        self.sum_descent_rate_mm_s += descent_rate_mm_s;
+        self.sum_dac_v += dac_v;
+        self.sample_count += 1;
+
+        if self.sample_count >= Self::WINDOW_TICKS {
+            let avg_descent_rate_mm_s = (self.sum_descent_rate_mm_s / self.sample_count as f32)
+                .clamp(0.5, constants::MAX_DESCENT_RATE_MM_S);
+            let avg_dac_v = (self.sum_dac_v / self.sample_count as f32)
+                .clamp(constants::DAC_V_AT_25MM, constants::DAC_V_AT_5MM);
+
+            self.calculated_neutral_v = (avg_descent_rate_mm_s / target_descent_rate_mm_s) * avg_dac_v;
+            self.reset();
         }
+
+        dac_v
     }
 
-    fn velocity(&self) -> f32 {
-        self.velocity
+    /// Latest calculated neutral (hover) DAC voltage
+    fn neutral_voltage(&self) -> f32 {
+        self.calculated_neutral_v
+    }
+}
+
+/// Final vertical speed/acceleration saturation on the commanded height
+///
+/// Applied last, after the trajectory and any safety correction, so no path
+/// into [`HeightController::update`]'s `corrected_height` -- nominal
+/// trajectory, oscillation/temp safety spike, or bobble -- can ask the coil
+/// to move faster than [`constants::MAX_DESCENT_RATE_MM_S`]/
+/// [`constants::MAX_ASCENT_RATE_MM_S`] and [`constants::OUTPUT_MAX_ACCEL_MM_S2`]
+/// allow. Mirrors the climb-rate/acceleration limiter in flight guidance
+/// loops: convert the per-tick commanded height change into a velocity,
+/// bound it, then bound its change from the previous tick, and re-integrate
+/// to get the limited setpoint.
+struct OutputSlewLimiter {
+    // `None` until the first `update()`, which seeds it from that call's
+    // own target instead of an arbitrary literal -- otherwise the first
+    // tick's rate is computed against a stale height (e.g. 0.0) and the
+    // limiter itself becomes the thing forcing a slammed setpoint
+    last_height_mm: Option<f32>,
+    last_cmd_vel_mm_s: f32,
+}
+
+impl OutputSlewLimiter {
+    fn new() -> Self {
+        Self { last_height_mm: None, last_cmd_vel_mm_s: 0.0 }
+    }
+
+    /// Slew-limit `target_height_mm` against the previous tick's output,
+    /// returning the limited setpoint
+    fn update(&mut self, target_height_mm: f32, dt: f32) -> f32 {
+        let last_height_mm = self.last_height_mm.unwrap_or(target_height_mm);
+
+        let mut cmd_vel = (target_height_mm - last_height_mm) / dt;
+        cmd_vel = cmd_vel.clamp(-constants::MAX_DESCENT_RATE_MM_S, constants::MAX_ASCENT_RATE_MM_S);
+
+        let max_delta = constants::OUTPUT_MAX_ACCEL_MM_S2 * dt;
+        cmd_vel = (cmd_vel - self.last_cmd_vel_mm_s).clamp(-max_delta, max_delta) + self.last_cmd_vel_mm_s;
+
+        let limited_height_mm = last_height_mm + cmd_vel * dt;
+
+        self.last_height_mm = Some(limited_height_mm);
+        self.last_cmd_vel_mm_s = cmd_vel;
+
+        limited_height_mm
     }
 }
 
@@ -421,12 +647,12 @@ mod tests {
 
     #[test]
     fn test_velocity_filter() {
-        let mut filter = VelocityFilter::new();
+        let mut filter = ComplementaryVelocityFilter::new();
 
-        // Simulate rising at 10mm/s
-        for i in 0..10 {
-            let height = 10.0 + (i as f32) * 0.1; // 0.1mm per step
-            filter.update(height, 0.01); // 100Hz = 0.01s period
+        // Simulate rising at 10mm/s, no modeled acceleration
+        for i in 0..500 {
+            let height = 10.0 + (i as f32) * 0.1; // 0.1mm per 10ms step = 10mm/s
+            filter.update(height, 0.0, 0.01); // 100Hz = 0.01s period
         }
 
         // Velocity should converge to ~10mm/s
@@ -434,6 +660,61 @@ mod tests {
         assert!(v > 5.0 && v < 15.0);
     }
 
+    #[test]
+    fn test_modeled_accel_sign_matches_commanded_voltage_direction() {
+        let mut controller = HeightController::new();
+
+        // Command a voltage above the regulator's neutral estimate. Per
+        // the calibration curve, DAC voltage falls as height rises, so
+        // this asks for a *lower* height than hover -- the modeled
+        // acceleration fed into the velocity filter should be negative
+        // (downward), not positive.
+        controller.last_dac_voltage = controller.descent_regulator.neutral_voltage() + 1.0;
+
+        // ADC value mid-curve (15mm, safely inside HEIGHT_MIN/MAX_MM), with
+        // the filter started at that height so the only thing driving the
+        // velocity estimate is modeled accel. One tick only: `update`
+        // replaces `last_dac_voltage` with its own output afterwards.
+        controller.velocity_filter.integ3 = 15.0;
+        controller.update(2600, true, 25.0).unwrap();
+
+        assert!(controller.state().velocity_mm_s < 0.0);
+    }
+
+    #[test]
+    fn test_descent_rate_regulator_converges_neutral_voltage() {
+        let mut regulator = DescentRateRegulator::new();
+        let target_descent_rate = 5.0;
+
+        // Run enough ticks to complete a full observation window
+        for _ in 0..60 {
+            regulator.update(-target_descent_rate, target_descent_rate);
+        }
+
+        // Having tracked the target descent rate throughout the window,
+        // the neutral estimate should settle near the commanded voltage
+        let neutral = regulator.neutral_voltage();
+        assert!(neutral >= constants::DAC_V_AT_25MM && neutral <= constants::DAC_V_AT_5MM);
+    }
+
+    #[test]
+    fn test_descent_rate_regulator_trims_correct_direction() {
+        // Descending slower than commanded: a positive, sustained error.
+        // Since DAC voltage falls as commanded height rises, speeding up
+        // the descent means *raising* the voltage, not lowering it.
+        let mut too_slow = DescentRateRegulator::new();
+        let neutral = too_slow.neutral_voltage();
+        let dac_v = too_slow.update(-2.0, 5.0);
+        assert!(dac_v > neutral);
+
+        // Descending faster than commanded: a negative, sustained error --
+        // the voltage should come down to slow the descent back toward target.
+        let mut too_fast = DescentRateRegulator::new();
+        let neutral = too_fast.neutral_voltage();
+        let dac_v = too_fast.update(-10.0, 5.0);
+        assert!(dac_v < neutral);
+    }
+
     #[test]
     fn test_oscillation_detector() {
         let mut detector = OscillationDetector::new();
@@ -448,4 +729,47 @@ mod tests {
         let amp = detector.amplitude();
         assert!(amp > 1.5 && amp < 2.5);
     }
+
+    #[test]
+    fn test_output_slew_limiter_seeds_from_first_target() {
+        let mut limiter = OutputSlewLimiter::new();
+
+        // The very first tick must not command a jump toward a stale
+        // implicit starting height -- it should seed from (and therefore
+        // hold at) whatever the first target happens to be
+        let limited = limiter.update(17.0, 0.01);
+        assert_eq!(limited, 17.0);
+    }
+
+    #[test]
+    fn test_output_slew_limiter_bounds_descent_rate() {
+        let mut limiter = OutputSlewLimiter::new();
+        let dt = 0.01;
+
+        // Seed at 25mm, then demand an instant jump to 0mm -- far faster
+        // than MAX_DESCENT_RATE_MM_S -- and check the commanded rate never
+        // exceeds it on any tick, during the accel-limited ramp or after
+        let mut last = limiter.update(25.0, dt);
+        for _ in 0..200 {
+            let limited = limiter.update(0.0, dt);
+            let rate = (last - limited) / dt;
+            assert!(rate <= constants::MAX_DESCENT_RATE_MM_S + 0.1);
+            last = limited;
+        }
+    }
+
+    #[test]
+    fn test_output_slew_limiter_bounds_acceleration() {
+        let mut limiter = OutputSlewLimiter::new();
+        let dt = 0.01;
+
+        // Seed at rest, then demand a huge jump: the commanded velocity on
+        // this next tick is bounded by the acceleration limit
+        // (0 -> OUTPUT_MAX_ACCEL_MM_S2 * dt), not by the (much larger) rate
+        // limit
+        let seed = limiter.update(0.0, dt);
+        let limited = limiter.update(100.0, dt);
+        let expected_max_vel = constants::OUTPUT_MAX_ACCEL_MM_S2 * dt;
+        assert!(((limited - seed) / dt) <= expected_max_vel + 0.01);
+    }
 }
@@ -34,11 +34,13 @@ mod controller;
 mod trajectory;
 mod safety;
 mod calibration;
+mod recalibration;
 
 pub use controller::HeightController;
-pub use trajectory::{HeightTrajectory, BobbleAnimation};
-pub use safety::{LevitationSafetyVerifier, SafetyResult, SafetyCode};
-pub use calibration::CalibrationData;
+pub use trajectory::{HeightTrajectory, BobbleAnimation, ReferenceModel};
+pub use safety::{LevitationSafetyVerifier, SafetyResult, SafetyCode, EmergencyFlag, EMERGENCY_FLAG};
+pub use calibration::{CalibrationData, CalibrationBuilder, AdcFilter, AdcFilterMode};
+pub use recalibration::{HeightCurveFitter, CouplingFitter, CouplingModel};
 
 use core::f32::consts::PI;
 
@@ -102,9 +104,14 @@ impl Default for LevitationMode {
 /// Current state of the levitation system
 #[derive(Debug, Clone, Copy, Default)]
 pub struct LevitationState {
-    /// Current height above base (mm)
+    /// Current height above base (mm), from the filtered ADC reading
     pub height_mm: f32,
 
+    /// Complementary-filtered height estimate (mm) -- smoother than
+    /// `height_mm` at the cost of the filter's own lag; informational only,
+    /// `height_mm` remains the height the controller acts on
+    pub height_estimate_mm: f32,
+
     /// Vertical velocity (mm/s, positive = rising)
     pub velocity_mm_s: f32,
 
@@ -147,6 +154,23 @@ pub mod constants {
     /// Maximum descent rate (mm/s)
     pub const MAX_DESCENT_RATE_MM_S: f32 = 15.0;
 
+    /// [`crate::levitation::ReferenceModel`] velocity bound (mm/s) -- actually
+    /// enforced on the output, unlike a plain fixed-duration trajectory's
+    /// implied rate
+    pub const MAX_VEL_MM_S: f32 = 20.0;
+
+    /// [`crate::levitation::ReferenceModel`] acceleration bound (mm/s^2)
+    pub const MAX_ACCEL_MM_S2: f32 = 80.0;
+
+    /// Maximum ascent rate (mm/s) enforced by the controller's final
+    /// output slew limiter -- looser than [`MAX_DESCENT_RATE_MM_S`] since
+    /// rising has no touchdown-impact risk
+    pub const MAX_ASCENT_RATE_MM_S: f32 = 20.0;
+
+    /// Controller output slew limiter's acceleration bound (mm/s^2) on the
+    /// final commanded setpoint
+    pub const OUTPUT_MAX_ACCEL_MM_S2: f32 = 80.0;
+
     /// Maximum bobble amplitude (mm)
     pub const MAX_BOBBLE_AMPLITUDE_MM: f32 = 8.0;
 
@@ -162,6 +186,12 @@ pub mod constants {
     /// Control loop period (ms)
     pub const CONTROL_PERIOD_MS: u64 = 1000 / CONTROL_RATE_HZ as u64;
 
+    /// Coarse commanded-force-vs-gravity model gain (mm/s^2 per volt of
+    /// DAC deviation from the estimated neutral/hover voltage), used to feed
+    /// the controller's complementary velocity filter a modeled
+    /// acceleration until a real accelerometer is wired up
+    pub const DAC_ACCEL_GAIN_MM_S2_PER_V: f32 = 40.0;
+
     /// DAC voltage at 5mm height
     pub const DAC_V_AT_5MM: f32 = 2.5;
 
@@ -186,21 +216,40 @@ pub fn estimate_coupling(height_mm: f32) -> f32 {
     0.9 * libm::expf(-height_mm / 15.0)
 }
 
+/// Nominal transmit-coil quality factor, shared by [`estimate_efficiency`]
+/// and its inverse [`efficiency_to_coupling`]
+const WPT_Q_TX: f32 = 200.0;
+
+/// Nominal receive-coil quality factor, shared by [`estimate_efficiency`]
+/// and its inverse [`efficiency_to_coupling`]
+const WPT_Q_RX: f32 = 150.0;
+
 /// Estimate WPT efficiency from coupling coefficient
 ///
 /// η = k² × Q_tx × Q_rx / (1 + k² × Q_tx × Q_rx)
 ///
 /// With Q_tx ≈ 200, Q_rx ≈ 150
 pub fn estimate_efficiency(k: f32) -> f32 {
-    const Q_TX: f32 = 200.0;
-    const Q_RX: f32 = 150.0;
-
     let k_sq = k * k;
-    let q_product = Q_TX * Q_RX;
+    let q_product = WPT_Q_TX * WPT_Q_RX;
 
     k_sq * q_product / (1.0 + k_sq * q_product)
 }
 
+/// Invert [`estimate_efficiency`]: recover the coupling coefficient that
+/// would produce a measured efficiency
+///
+/// k = sqrt(η / (Q_tx × Q_rx × (1 - η)))
+///
+/// Used by [`recalibration::CouplingFitter`] to turn jig-measured
+/// efficiency samples into the `k` values its fit actually operates on.
+pub fn efficiency_to_coupling(efficiency: f32) -> f32 {
+    let eta = efficiency.clamp(0.0, 0.999);
+    let q_product = WPT_Q_TX * WPT_Q_RX;
+
+    libm::sqrtf(eta / (q_product * (1.0 - eta)))
+}
+
 /// Calculate optimal WPT frequency for given coupling
 ///
 /// f_resonant = f_0 * sqrt(1 - k) for undercoupled operation
@@ -242,6 +291,16 @@ mod tests {
         assert!(eta_low > 0.5 && eta_low < 0.9);
     }
 
+    #[test]
+    fn test_efficiency_to_coupling_inverts_estimate_efficiency() {
+        // Small k, so Q_tx*Q_rx doesn't saturate the efficiency near 1.0
+        // and clip the round-trip at the 0.999 clamp
+        let k = 0.01;
+        let eta = estimate_efficiency(k);
+        let recovered = efficiency_to_coupling(eta);
+        assert!((recovered - k).abs() < 0.001);
+    }
+
     #[test]
     fn test_wpt_frequency() {
         // At k=0, frequency should be f_base
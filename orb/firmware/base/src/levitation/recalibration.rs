@@ -0,0 +1,451 @@
+//! Online re-calibration via Levenberg–Marquardt nonlinear least squares
+//!
+//! [`CalibrationBuilder`](super::CalibrationBuilder) fits the ADC/height and
+//! height/DAC relationships as straight lines, which is a fine local
+//! approximation but doesn't capture the Hall sensor's actual exponential
+//! field falloff, or the coupling-coefficient decay used by
+//! [`super::estimate_coupling`]. The fitters here instead fit the physical
+//! exponential models directly against jig-measured sample pairs, using
+//! Levenberg–Marquardt: each iteration solves the damped normal equations
+//! `(JᵀJ + λ·diag(JᵀJ))·δ = Jᵀr` for the parameter update `δ`, growing `λ`
+//! (more gradient-descent-like) after a worse step and shrinking it (more
+//! Gauss-Newton-like) after an improvement.
+
+use crate::error::BaseError;
+
+use super::calibration::{CalibrationData, CalibrationPoint};
+
+/// Max samples retained by [`HeightCurveFitter`]/[`CouplingFitter`] -- a
+/// guided field-calibration pass collects a few dozen points, not
+/// thousands, and each LM iteration costs `O(count * P^2)`.
+const MAX_SAMPLES: usize = 32;
+
+/// Small Levenberg–Marquardt nonlinear least-squares solver fit to `P`
+/// parameters over up to [`MAX_SAMPLES`] `(x, y)` pairs -- sized for the
+/// exponential models below, not a general-purpose optimizer.
+struct LevenbergMarquardt<const P: usize> {
+    lambda: f32,
+}
+
+impl<const P: usize> LevenbergMarquardt<P> {
+    const MAX_ITERATIONS: u32 = 50;
+    const LAMBDA_INIT: f32 = 1e-2;
+    const LAMBDA_UP: f32 = 10.0;
+    const LAMBDA_DOWN: f32 = 0.1;
+    const CONVERGENCE_EPS: f32 = 1e-8;
+
+    fn new() -> Self {
+        Self { lambda: Self::LAMBDA_INIT }
+    }
+
+    /// Fit `params`, starting from the given initial guess, against
+    /// `xs`/`ys` (first `count` entries). `model(x, params)` predicts `y`;
+    /// `jacobian(x, params)` returns `∂model/∂params[i]` for each `i`.
+    /// Returns [`BaseError::AdcError`] if the damped normal equations are
+    /// ever singular (a pathological Jacobian, e.g. from degenerate
+    /// samples).
+    fn fit(
+        &mut self,
+        xs: &[f32],
+        ys: &[f32],
+        count: usize,
+        initial: [f32; P],
+        model: impl Fn(f32, &[f32; P]) -> f32,
+        jacobian: impl Fn(f32, &[f32; P]) -> [f32; P],
+    ) -> Result<[f32; P], BaseError> {
+        let mut params = initial;
+        let mut cost = Self::sum_sq_residual(xs, ys, count, &params, &model);
+
+        for _ in 0..Self::MAX_ITERATIONS {
+            let mut jtj = [[0.0f32; P]; P];
+            let mut jtr = [0.0f32; P];
+
+            for i in 0..count {
+                let residual = ys[i] - model(xs[i], &params);
+                let j = jacobian(xs[i], &params);
+                for a in 0..P {
+                    jtr[a] += j[a] * residual;
+                    for b in 0..P {
+                        jtj[a][b] += j[a] * j[b];
+                    }
+                }
+            }
+
+            let mut damped = jtj;
+            for a in 0..P {
+                damped[a][a] += self.lambda * jtj[a][a];
+            }
+
+            let delta = solve_linear_system(damped, jtr).ok_or(BaseError::AdcError)?;
+
+            let mut candidate = params;
+            for a in 0..P {
+                candidate[a] += delta[a];
+            }
+
+            let new_cost = Self::sum_sq_residual(xs, ys, count, &candidate, &model);
+
+            if new_cost < cost {
+                let converged = cost - new_cost < Self::CONVERGENCE_EPS;
+                params = candidate;
+                cost = new_cost;
+                self.lambda *= Self::LAMBDA_DOWN;
+                if converged {
+                    break;
+                }
+            } else {
+                self.lambda *= Self::LAMBDA_UP;
+            }
+        }
+
+        Ok(params)
+    }
+
+    fn sum_sq_residual(
+        xs: &[f32],
+        ys: &[f32],
+        count: usize,
+        params: &[f32; P],
+        model: &impl Fn(f32, &[f32; P]) -> f32,
+    ) -> f32 {
+        let mut sum = 0.0;
+        for i in 0..count {
+            let residual = ys[i] - model(xs[i], params);
+            sum += residual * residual;
+        }
+        sum
+    }
+}
+
+/// Solve the `P`x`P` linear system `a * x = b` via Gaussian elimination
+/// with partial pivoting. Returns `None` if `a` is singular to working
+/// precision.
+fn solve_linear_system<const P: usize>(mut a: [[f32; P]; P], mut b: [f32; P]) -> Option<[f32; P]> {
+    for col in 0..P {
+        let mut pivot = col;
+        for row in (col + 1)..P {
+            if libm::fabsf(a[row][col]) > libm::fabsf(a[pivot][col]) {
+                pivot = row;
+            }
+        }
+        if libm::fabsf(a[pivot][col]) <= f32::EPSILON {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        for row in (col + 1)..P {
+            let factor = a[row][col] / a[col][col];
+            for k in col..P {
+                a[row][k] -= factor * a[col][k];
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    let mut x = [0.0f32; P];
+    for row in (0..P).rev() {
+        let mut sum = b[row];
+        for k in (row + 1)..P {
+            sum -= a[row][k] * x[k];
+        }
+        x[row] = sum / a[row][row];
+    }
+
+    Some(x)
+}
+
+/// Nonlinear re-fit of the Hall-sensor ADC/height curve
+///
+/// Fits `adc = a*exp(-height/b) + c` to measured `(known_height_mm,
+/// measured_adc)` pairs via Levenberg–Marquardt -- modeling the sensor's
+/// actual exponential field falloff, rather than
+/// [`CalibrationBuilder`](super::CalibrationBuilder)'s locally-linear
+/// approximation. `height -> dac_voltage` is still fit as an ordinary
+/// least-squares line from the paired `applied_dac_voltage` samples, since
+/// that relationship is close to linear over the operating range.
+pub struct HeightCurveFitter {
+    heights: [f32; MAX_SAMPLES],
+    adcs: [f32; MAX_SAMPLES],
+    dacs: [f32; MAX_SAMPLES],
+    count: usize,
+}
+
+impl Default for HeightCurveFitter {
+    fn default() -> Self {
+        Self {
+            heights: [0.0; MAX_SAMPLES],
+            adcs: [0.0; MAX_SAMPLES],
+            dacs: [0.0; MAX_SAMPLES],
+            count: 0,
+        }
+    }
+}
+
+impl HeightCurveFitter {
+    /// Create a new, empty sample collector
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one measured sample at a known, commanded height; ignored once
+    /// [`MAX_SAMPLES`] have been collected
+    pub fn push_sample(&mut self, known_height_mm: f32, measured_adc: u16, applied_dac_voltage: f32) {
+        if self.count >= MAX_SAMPLES {
+            return;
+        }
+        self.heights[self.count] = known_height_mm;
+        self.adcs[self.count] = measured_adc as f32;
+        self.dacs[self.count] = applied_dac_voltage;
+        self.count += 1;
+    }
+
+    /// Number of samples collected so far
+    pub fn sample_count(&self) -> usize {
+        self.count
+    }
+
+    /// Fit the accumulated samples into a fresh `CalibrationData`
+    ///
+    /// `version` is bumped past `previous.version()`, `serial` and the
+    /// tempco triple are carried over unchanged. Returns
+    /// [`BaseError::AdcError`] if fewer than 3 samples were collected, the
+    /// LM fit doesn't converge to physically sane `a`/`b` (both must stay
+    /// positive -- a decaying-with-height field), or the regenerated curve
+    /// fails [`CalibrationData::is_valid`].
+    pub fn build(&self, previous: &CalibrationData) -> Result<CalibrationData, BaseError> {
+        if self.count < 3 {
+            return Err(BaseError::AdcError);
+        }
+
+        let (min_height, max_height) = self.height_range();
+
+        // Seed from the samples' own ADC span, and a height-decay scale
+        // comparable to the coupling model's nominal 15mm (see
+        // `super::estimate_coupling`) -- both curves share the same
+        // underlying magnetic field falloff.
+        let adc_min = self.adcs[..self.count].iter().cloned().fold(f32::MAX, f32::min);
+        let adc_max = self.adcs[..self.count].iter().cloned().fold(f32::MIN, f32::max);
+        let initial = [adc_max - adc_min, 15.0, adc_min];
+
+        let mut lm = LevenbergMarquardt::<3>::new();
+        let params = lm.fit(
+            &self.heights,
+            &self.adcs,
+            self.count,
+            initial,
+            |h, p| p[0] * libm::expf(-h / p[1]) + p[2],
+            |h, p| {
+                let e = libm::expf(-h / p[1]);
+                [e, p[0] * e * (h / (p[1] * p[1])), 1.0]
+            },
+        )?;
+        let (a, b, c) = (params[0], params[1], params[2]);
+        if a <= 0.0 || b <= 0.0 {
+            return Err(BaseError::AdcError);
+        }
+
+        let (p, q) = self.fit_dac_line()?;
+
+        // Regenerate 5 evenly-spaced points spanning the observed height
+        // range, same as `CalibrationBuilder::build`
+        let mut points = [CalibrationPoint::default(); 5];
+        for (i, point) in points.iter_mut().enumerate() {
+            let height = min_height + (max_height - min_height) * (i as f32 / 4.0);
+            let adc = (a * libm::expf(-height / b) + c).round().clamp(0.0, u16::MAX as f32) as u16;
+            point.height_mm = height;
+            point.adc_value = adc;
+            point.dac_voltage = p * height + q;
+        }
+
+        let mut data = CalibrationData::from_points(&points);
+        data.set_version(previous.version().wrapping_add(1));
+        data.set_serial(previous.serial());
+        let (t_ref_c, adc_tempco, dac_tempco) = previous.tempco();
+        data.set_tempco(t_ref_c, adc_tempco, dac_tempco);
+
+        if !data.is_valid() {
+            return Err(BaseError::AdcError);
+        }
+
+        Ok(data)
+    }
+
+    fn height_range(&self) -> (f32, f32) {
+        let min = self.heights[..self.count].iter().cloned().fold(f32::MAX, f32::min);
+        let max = self.heights[..self.count].iter().cloned().fold(f32::MIN, f32::max);
+        (min, max)
+    }
+
+    /// Ordinary least squares fit of `dac_voltage = p*height + q`
+    fn fit_dac_line(&self) -> Result<(f32, f32), BaseError> {
+        let n = self.count as f32;
+        let (mut sum_h, mut sum_dac, mut sum_h_dac, mut sum_h2) = (0.0, 0.0, 0.0, 0.0);
+        for i in 0..self.count {
+            let h = self.heights[i];
+            let dac = self.dacs[i];
+            sum_h += h;
+            sum_dac += dac;
+            sum_h_dac += h * dac;
+            sum_h2 += h * h;
+        }
+
+        let denom = n * sum_h2 - sum_h * sum_h;
+        if denom.abs() <= f32::EPSILON {
+            return Err(BaseError::AdcError);
+        }
+
+        let p = (n * sum_h_dac - sum_h * sum_dac) / denom;
+        let q = (sum_dac - p * sum_h) / n;
+        Ok((p, q))
+    }
+}
+
+/// Nonlinear re-fit of the coupling-coefficient decay model
+///
+/// Fits `k = a*exp(-height/b)` (see [`super::estimate_coupling`]'s nominal
+/// `a=0.9`, `b=15.0`) to measured `(height_mm, measured_efficiency)` pairs
+/// via Levenberg–Marquardt, after inverting each efficiency sample to a
+/// coupling coefficient with [`super::efficiency_to_coupling`].
+pub struct CouplingFitter {
+    heights: [f32; MAX_SAMPLES],
+    couplings: [f32; MAX_SAMPLES],
+    count: usize,
+}
+
+impl Default for CouplingFitter {
+    fn default() -> Self {
+        Self { heights: [0.0; MAX_SAMPLES], couplings: [0.0; MAX_SAMPLES], count: 0 }
+    }
+}
+
+impl CouplingFitter {
+    /// Create a new, empty sample collector
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one measured `(height_mm, measured_efficiency)` sample; ignored
+    /// once [`MAX_SAMPLES`] have been collected
+    pub fn push_sample(&mut self, height_mm: f32, measured_efficiency: f32) {
+        if self.count >= MAX_SAMPLES {
+            return;
+        }
+        self.heights[self.count] = height_mm;
+        self.couplings[self.count] = super::efficiency_to_coupling(measured_efficiency);
+        self.count += 1;
+    }
+
+    /// Number of samples collected so far
+    pub fn sample_count(&self) -> usize {
+        self.count
+    }
+
+    /// Fit `a`/`b`, seeded from [`super::estimate_coupling`]'s nominal
+    /// values. Returns [`BaseError::AdcError`] if fewer than 3 samples were
+    /// collected or the fit doesn't converge to a positive `a`/`b`.
+    pub fn fit(&self) -> Result<CouplingModel, BaseError> {
+        if self.count < 3 {
+            return Err(BaseError::AdcError);
+        }
+
+        let mut lm = LevenbergMarquardt::<2>::new();
+        let params = lm.fit(
+            &self.heights,
+            &self.couplings,
+            self.count,
+            [0.9, 15.0],
+            |h, p| p[0] * libm::expf(-h / p[1]),
+            |h, p| {
+                let e = libm::expf(-h / p[1]);
+                [e, p[0] * e * (h / (p[1] * p[1]))]
+            },
+        )?;
+
+        if params[0] <= 0.0 || params[1] <= 0.0 {
+            return Err(BaseError::AdcError);
+        }
+
+        Ok(CouplingModel { a: params[0], b: params[1] })
+    }
+}
+
+/// Fitted coupling-decay coefficients refining
+/// [`super::estimate_coupling`]'s nominal `a=0.9`, `b=15.0`
+#[derive(Debug, Clone, Copy)]
+pub struct CouplingModel {
+    pub a: f32,
+    pub b: f32,
+}
+
+impl CouplingModel {
+    /// Coupling coefficient at `height_mm` under this fitted model
+    pub fn coupling(&self, height_mm: f32) -> f32 {
+        self.a * libm::expf(-height_mm / self.b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_height_curve_fitter_recovers_known_model() {
+        // adc = 3000*exp(-height/12) + 500
+        let (a, b, c) = (3000.0, 12.0, 500.0);
+
+        let mut fitter = HeightCurveFitter::new();
+        let heights = [5.0, 8.0, 11.0, 14.0, 17.0, 20.0, 23.0];
+        for h in heights {
+            let adc = (a * libm::expf(-h / b) + c).round() as u16;
+            let dac = 2.7 - 0.09 * h; // linear DAC relationship, a little flatter than the default calibration's
+            fitter.push_sample(h, adc, dac);
+        }
+
+        let previous = CalibrationData::default();
+        let fitted = fitter.build(&previous).unwrap();
+
+        assert!(fitted.is_valid());
+        assert_eq!(fitted.version(), previous.version() + 1);
+
+        // Interpolated curve should reproduce the underlying model closely
+        // at a height between calibration points
+        let expected_adc = a * libm::expf(-13.0 / b) + c;
+        assert!((fitted.adc_to_height_raw(expected_adc as u16) - 13.0).abs() < 1.5);
+    }
+
+    #[test]
+    fn test_height_curve_fitter_rejects_too_few_samples() {
+        let mut fitter = HeightCurveFitter::new();
+        fitter.push_sample(5.0, 3800, 2.5);
+        fitter.push_sample(15.0, 2600, 1.5);
+
+        assert!(matches!(fitter.build(&CalibrationData::default()), Err(BaseError::AdcError)));
+    }
+
+    #[test]
+    fn test_coupling_fitter_recovers_known_model() {
+        // Small `k` throughout, so `estimate_efficiency`'s huge Q product
+        // doesn't saturate every sample to the same near-1.0 efficiency
+        let (a, b) = (0.03, 14.0);
+
+        let mut fitter = CouplingFitter::new();
+        for h in [5.0, 9.0, 13.0, 17.0, 21.0, 25.0] {
+            let k = a * libm::expf(-h / b);
+            let efficiency = super::super::estimate_efficiency(k);
+            fitter.push_sample(h, efficiency);
+        }
+
+        let model = fitter.fit().unwrap();
+        assert!((model.a - a).abs() < 0.005);
+        assert!((model.b - b).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_coupling_fitter_rejects_too_few_samples() {
+        let mut fitter = CouplingFitter::new();
+        fitter.push_sample(5.0, 0.9);
+
+        assert!(matches!(fitter.fit(), Err(BaseError::AdcError)));
+    }
+}
@@ -14,6 +14,8 @@
 //! When h(x) approaches 0, the system takes corrective action.
 //! When h(x) < 0, emergency procedures are triggered.
 
+use core::sync::atomic::{AtomicBool, Ordering};
+
 use super::{LevitationState, constants};
 
 /// Safety verification result
@@ -357,6 +359,42 @@ impl SafetyInterlockManager {
     }
 }
 
+/// Interrupt-latched hardware fault flag
+///
+/// Set directly from GPIO edge handlers in `safety_monitor_task` (NTC
+/// comparator alert, WPT foreign-object fault) rather than waiting for the
+/// next periodic barrier-function evaluation. `HeightController::update`
+/// reads this every cycle and, once set, immediately commands a descent
+/// regardless of what the slower CBF check thinks. It stays latched until
+/// `clear()` is called after manual inspection -- a real over-temperature
+/// or FOD event must not silently clear itself on the next cool reading.
+pub struct EmergencyFlag(AtomicBool);
+
+impl EmergencyFlag {
+    /// Create a new, unlatched flag
+    pub const fn new() -> Self {
+        Self(AtomicBool::new(false))
+    }
+
+    /// Latch the flag (called from an interrupt/edge handler)
+    pub fn latch(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Check whether the flag is latched
+    pub fn is_set(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Clear the flag (requires explicit operator/firmware action)
+    pub fn clear(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Shared interrupt-latched emergency flag for over-temperature / WPT fault alerts
+pub static EMERGENCY_FLAG: EmergencyFlag = EmergencyFlag::new();
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -364,6 +402,7 @@ mod tests {
     fn make_safe_state() -> LevitationState {
         LevitationState {
             height_mm: 15.0,
+            height_estimate_mm: 15.0,
             velocity_mm_s: 0.0,
             oscillation_amplitude_mm: 1.0,
             electromagnet_temp_c: 50.0,
@@ -456,4 +495,16 @@ mod tests {
         }
         assert!(manager.is_emergency());
     }
+
+    #[test]
+    fn test_emergency_flag_latch_and_clear() {
+        let flag = EmergencyFlag::new();
+        assert!(!flag.is_set());
+
+        flag.latch();
+        assert!(flag.is_set());
+
+        flag.clear();
+        assert!(!flag.is_set());
+    }
 }
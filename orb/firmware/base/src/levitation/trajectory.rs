@@ -1,17 +1,22 @@
 //! Smooth trajectory generation for height control
 //!
-//! Provides S-curve trajectories for smooth height transitions and
-//! periodic animations for bobble effects.
+//! Provides S-curve trajectories for smooth height transitions, periodic
+//! animations for bobble effects, and a [`ReferenceModel`] second-order
+//! filter for duration-agnostic, rate-limited transitions.
 
 use core::f32::consts::PI;
-use libm::{sinf, cosf};
+use libm::{sinf, cosf, sqrtf};
 
 /// Trajectory generator for smooth height transitions
 ///
-/// Uses an S-curve (smoothstep) profile for natural motion:
-/// - Zero velocity at start and end
-/// - Maximum velocity at midpoint
-/// - No jerky accelerations
+/// Two ways to build one:
+/// - [`HeightTrajectory::new`]: fixed-duration smoothstep (3s² - 2s³). Zero
+///   velocity at the endpoints, but peak acceleration scales with however
+///   short a duration the caller picks -- risky for a current-limited coil.
+/// - [`HeightTrajectory::new_jerk_limited`]: time-optimal seven-segment
+///   S-curve bounded by explicit velocity/acceleration/jerk limits, so the
+///   caller can never accidentally command more coil current slew than the
+///   hardware can deliver.
 #[derive(Debug, Clone, Copy)]
 pub struct HeightTrajectory {
     /// Starting height (mm)
@@ -25,6 +30,173 @@ pub struct HeightTrajectory {
 
     /// Elapsed time (seconds)
     pub elapsed: f32,
+
+    /// Segment timing for a jerk-limited profile; `None` means smoothstep
+    scurve: Option<SCurveProfile>,
+}
+
+/// Segment timing for a jerk-limited (seven-segment) S-curve
+///
+/// All magnitudes are unsigned; [`HeightTrajectory`] applies the direction
+/// (start -> target) and the start-height offset on top.
+#[derive(Debug, Clone, Copy)]
+struct SCurveProfile {
+    /// Duration of each jerk (ramp) segment
+    tj: f32,
+    /// Duration of the constant-accel/decel segment (0 for a triangular ramp)
+    tc: f32,
+    /// Duration of one full ramp (accel or decel): `2*tj + tc`
+    ta: f32,
+    /// Duration of the constant-velocity cruise segment (0 if never reached)
+    tcruise: f32,
+    /// Peak acceleration magnitude actually reached
+    peak_accel: f32,
+    /// Cruise velocity magnitude actually reached
+    cruise_vel: f32,
+    /// Jerk magnitude used for all four ramp sub-segments
+    jerk: f32,
+    /// +1.0 if target > start, else -1.0
+    direction: f32,
+}
+
+impl SCurveProfile {
+    /// Segment durations to ramp from 0 to `v` under jerk/accel limits
+    ///
+    /// Returns `(tj, tc, peak_accel)`. If `v` is small enough that `amax`
+    /// is never reached, the ramp collapses to a triangular jerk-up/
+    /// jerk-down shape (`tc == 0.0`).
+    fn ramp_segments(v: f32, amax: f32, jmax: f32) -> (f32, f32, f32) {
+        let tj_at_amax = amax / jmax;
+        let v_at_amax = amax * tj_at_amax; // velocity gained by a jerk-up/jerk-down ramp that just touches amax
+
+        if v >= v_at_amax {
+            let tc = (v - v_at_amax) / amax;
+            (tj_at_amax, tc, amax)
+        } else {
+            let tj = sqrtf(v / jmax);
+            (tj, 0.0, jmax * tj)
+        }
+    }
+
+    /// Solve the seven-segment profile moving `distance` (mm, unsigned)
+    /// under the given velocity/acceleration/jerk limits
+    fn solve(distance: f32, vmax: f32, amax: f32, jmax: f32, direction: f32) -> Self {
+        if distance <= 0.0 {
+            return Self {
+                tj: 0.0, tc: 0.0, ta: 0.0, tcruise: 0.0,
+                peak_accel: 0.0, cruise_vel: 0.0, jerk: jmax, direction,
+            };
+        }
+
+        let (tj, tc, peak_accel) = Self::ramp_segments(vmax, amax, jmax);
+        let ta = 2.0 * tj + tc;
+
+        // Does a full-speed trapezoidal profile (two ramps + cruise) fit?
+        // `vmax * ta` is the distance covered by the accel and decel ramps alone.
+        if vmax * ta <= distance {
+            let tcruise = (distance - vmax * ta) / vmax;
+            return Self { tj, tc, ta, tcruise, peak_accel, cruise_vel: vmax, jerk: jmax, direction };
+        }
+
+        // No cruise segment: find the peak velocity vc actually reached
+        // before the decel ramp must begin, i.e. `vc * ramp_duration(vc) == distance`.
+        let v_at_amax = amax * (amax / jmax);
+
+        // First try assuming a triangular ramp (amax never reached):
+        // distance = vc * (2*sqrt(vc/jmax)) => vc = (distance*sqrt(jmax)/2)^(2/3)
+        let vc_triangular = libm::powf(distance * sqrtf(jmax) / 2.0, 2.0 / 3.0);
+
+        if vc_triangular <= v_at_amax {
+            let tj = sqrtf(vc_triangular / jmax);
+            let ta = 2.0 * tj;
+            return Self {
+                tj, tc: 0.0, ta, tcruise: 0.0,
+                peak_accel: jmax * tj, cruise_vel: vc_triangular, jerk: jmax, direction,
+            };
+        }
+
+        // Otherwise it's trapezoidal: vc^2/amax + (amax/jmax)*vc - distance = 0
+        let b = amax / jmax;
+        let vc = amax * (-b + sqrtf(b * b + 4.0 * distance / amax)) / 2.0;
+        let tj = amax / jmax;
+        let tc = (vc - amax * tj) / amax;
+        let ta = 2.0 * tj + tc;
+
+        Self { tj, tc, ta, tcruise: 0.0, peak_accel: amax, cruise_vel: vc, jerk: jmax, direction }
+    }
+
+    /// Total duration of the profile
+    fn total_duration(&self) -> f32 {
+        2.0 * self.ta + self.tcruise
+    }
+
+    /// Unsigned (distance, velocity, acceleration) at time `t`, measured
+    /// from the start of the profile
+    fn sample_unsigned(&self, t: f32) -> (f32, f32, f32) {
+        let j = self.jerk;
+        let a_pk = self.peak_accel;
+        let v_c = self.cruise_vel;
+        let tj = self.tj;
+        let tc = self.tc;
+        let ta = self.ta;
+
+        // Position/velocity at the end of the constant-accel ramp-up phase
+        let v1 = 0.5 * j * tj * tj;
+        let s1 = (1.0 / 6.0) * j * tj * tj * tj;
+        let s2 = s1 + v1 * tc + 0.5 * a_pk * tc * tc;
+        let v2 = v1 + a_pk * tc;
+        let s_accel_end = s2 + v2 * tj + 0.5 * a_pk * tj * tj - (1.0 / 6.0) * j * tj * tj * tj;
+
+        if t < tj {
+            // Phase 1: jerk up
+            (j * t * t * t / 6.0, 0.5 * j * t * t, j * t)
+        } else if t < tj + tc {
+            // Phase 2: constant accel
+            let tau = t - tj;
+            (s1 + v1 * tau + 0.5 * a_pk * tau * tau, v1 + a_pk * tau, a_pk)
+        } else if t < ta {
+            // Phase 3: jerk down to cruise velocity
+            let tau = t - (tj + tc);
+            (
+                s2 + v2 * tau + 0.5 * a_pk * tau * tau - (1.0 / 6.0) * j * tau * tau * tau,
+                v2 + a_pk * tau - 0.5 * j * tau * tau,
+                a_pk - j * tau,
+            )
+        } else if t < ta + self.tcruise {
+            // Phase 4: cruise at constant velocity
+            let tau = t - ta;
+            (s_accel_end + v_c * tau, v_c, 0.0)
+        } else if t < ta + self.tcruise + tj {
+            // Phase 5: jerk down, decel begins
+            let tau = t - (ta + self.tcruise);
+            let s4 = s_accel_end + v_c * self.tcruise;
+            (
+                s4 + v_c * tau - (1.0 / 6.0) * j * tau * tau * tau,
+                v_c - 0.5 * j * tau * tau,
+                -j * tau,
+            )
+        } else if t < ta + self.tcruise + tj + tc {
+            // Phase 6: constant decel
+            let tau = t - (ta + self.tcruise + tj);
+            let s4 = s_accel_end + v_c * self.tcruise;
+            let v5 = v_c - 0.5 * j * tj * tj;
+            let s5 = s4 + v_c * tj - (1.0 / 6.0) * j * tj * tj * tj;
+            (s5 + v5 * tau - 0.5 * a_pk * tau * tau, v5 - a_pk * tau, -a_pk)
+        } else {
+            // Phase 7: jerk up, decel eases to zero and velocity reaches 0
+            let tau = (t - (ta + self.tcruise + tj + tc)).min(tj);
+            let s4 = s_accel_end + v_c * self.tcruise;
+            let v5 = v_c - 0.5 * j * tj * tj;
+            let s5 = s4 + v_c * tj - (1.0 / 6.0) * j * tj * tj * tj;
+            let v6 = v5 - a_pk * tc;
+            let s6 = s5 + v5 * tc - 0.5 * a_pk * tc * tc;
+            (
+                s6 + v6 * tau - 0.5 * a_pk * tau * tau + (1.0 / 6.0) * j * tau * tau * tau,
+                v6 - a_pk * tau + 0.5 * j * tau * tau,
+                -a_pk + j * tau,
+            )
+        }
+    }
 }
 
 impl HeightTrajectory {
@@ -35,6 +207,39 @@ impl HeightTrajectory {
             target_height: target,
             duration: duration_s.max(0.1), // Minimum 100ms
             elapsed: 0.0,
+            scurve: None,
+        }
+    }
+
+    /// Create a time-optimal, jerk-limited trajectory bounded by explicit
+    /// velocity/acceleration/jerk limits instead of a fixed duration
+    ///
+    /// Computes the classic seven-segment S-curve (jerk-up / const-accel /
+    /// jerk-down / const-vel / jerk-down / const-decel / jerk-up), collapsing
+    /// the segments that don't apply to short moves (no cruise segment, or a
+    /// triangular rather than trapezoidal accel ramp if `max_accel` is never
+    /// reached). This bounds coil current rate-of-change while still
+    /// reaching the target as fast as the hardware allows.
+    pub fn new_jerk_limited(start: f32, target: f32, max_vel: f32, max_accel: f32, max_jerk: f32) -> Self {
+        let delta = target - start;
+        let distance = delta.abs();
+        let direction = if delta >= 0.0 { 1.0 } else { -1.0 };
+
+        let profile = SCurveProfile::solve(
+            distance,
+            max_vel.max(0.001),
+            max_accel.max(0.001),
+            max_jerk.max(0.001),
+            direction,
+        );
+        let duration = profile.total_duration().max(0.001);
+
+        Self {
+            start_height: start,
+            target_height: target,
+            duration,
+            elapsed: 0.0,
+            scurve: Some(profile),
         }
     }
 
@@ -59,6 +264,11 @@ impl HeightTrajectory {
             return self.start_height;
         }
 
+        if let Some(ref profile) = self.scurve {
+            let (distance, _, _) = profile.sample_unsigned(t);
+            return self.start_height + profile.direction * distance;
+        }
+
         // Normalized time [0, 1]
         let s = t / self.duration;
 
@@ -74,6 +284,11 @@ impl HeightTrajectory {
             return 0.0;
         }
 
+        if let Some(ref profile) = self.scurve {
+            let (_, velocity, _) = profile.sample_unsigned(t);
+            return profile.direction * velocity;
+        }
+
         let s = t / self.duration;
 
         // Derivative of smoothstep: 6s(1-s)
@@ -82,6 +297,23 @@ impl HeightTrajectory {
         blend_deriv * (self.target_height - self.start_height) / self.duration
     }
 
+    /// Sample acceleration at time t (mm/s²)
+    ///
+    /// Zero for a fixed-duration smoothstep trajectory built with [`Self::new`]
+    /// -- only the jerk-limited profile tracks acceleration explicitly.
+    pub fn sample_acceleration(&self, t: f32) -> f32 {
+        if t <= 0.0 || t >= self.duration {
+            return 0.0;
+        }
+
+        if let Some(ref profile) = self.scurve {
+            let (_, _, acceleration) = profile.sample_unsigned(t);
+            return profile.direction * acceleration;
+        }
+
+        0.0
+    }
+
     /// Update elapsed time, returns true if complete
     pub fn update(&mut self, dt: f32) -> bool {
         self.elapsed += dt;
@@ -230,14 +462,113 @@ impl BobbleAnimation {
     }
 }
 
+/// Critically-dampable second-order reference model
+///
+/// Filters a commanded target through `accel = w^2*(target - pos) -
+/// 2*xi*w*vel`, integrated every tick, instead of interpolating over a
+/// fixed duration like [`HeightTrajectory::new`]. With `xi` near 1.0 the
+/// response is critically damped (no overshoot), and `vel`/`accel` stay
+/// bounded by `max_vel_mm_s`/`max_accel_mm_s2` regardless of how far the
+/// target is -- a fixed-duration trajectory has no such guarantee, since a
+/// short `duration_ms` implies whatever rate is needed to get there.
+///
+/// Retargeting mid-flight (`set_target`) is continuous: `pos`/`vel` carry
+/// over rather than restarting, which is what makes chained commands
+/// (float -> charging -> float) blend smoothly instead of jumping.
+#[derive(Debug, Clone, Copy)]
+pub struct ReferenceModel {
+    pos: f32,
+    vel: f32,
+    accel: f32,
+    target: f32,
+    max_vel_mm_s: f32,
+    max_accel_mm_s2: f32,
+}
+
+impl ReferenceModel {
+    /// Natural frequency (rad/s)
+    const W: f32 = 3.0;
+    /// Damping ratio -- 1.0 is critically damped (no overshoot)
+    const XI: f32 = 1.0;
+    /// `pos`/`vel` within this of the target counts as settled
+    const SETTLE_EPSILON: f32 = 0.05;
+
+    /// Create a model at rest at `initial_pos`, bounded by the crate's
+    /// default [`constants::MAX_VEL_MM_S`]/[`constants::MAX_ACCEL_MM_S2`]
+    pub fn new(initial_pos: f32) -> Self {
+        Self::with_limits(
+            initial_pos,
+            super::constants::MAX_VEL_MM_S,
+            super::constants::MAX_ACCEL_MM_S2,
+        )
+    }
+
+    /// Create a model at rest at `initial_pos` with explicit rate/accel bounds
+    pub fn with_limits(initial_pos: f32, max_vel_mm_s: f32, max_accel_mm_s2: f32) -> Self {
+        Self {
+            pos: initial_pos,
+            vel: 0.0,
+            accel: 0.0,
+            target: initial_pos,
+            max_vel_mm_s,
+            max_accel_mm_s2,
+        }
+    }
+
+    /// Command a new target; `pos`/`vel` are unaffected, so the filter
+    /// smoothly redirects toward it rather than jumping
+    pub fn set_target(&mut self, target: f32) {
+        self.target = target;
+    }
+
+    /// Advance the model by `dt` seconds, returning `(pos, vel)`
+    pub fn update(&mut self, dt: f32) -> (f32, f32) {
+        let mut accel = Self::W * Self::W * (self.target - self.pos) - 2.0 * Self::XI * Self::W * self.vel;
+        accel = accel.clamp(-self.max_accel_mm_s2, self.max_accel_mm_s2);
+
+        let mut vel = self.vel + accel * dt;
+        if vel.abs() > self.max_vel_mm_s {
+            vel = vel.clamp(-self.max_vel_mm_s, self.max_vel_mm_s);
+            // Already saturated: record zero rather than an accel that
+            // pushes vel further past the bound (as in the paparazzi
+            // reference-angle loop); the clamp above is what bounds vel
+            if (vel >= self.max_vel_mm_s && accel > 0.0) || (vel <= -self.max_vel_mm_s && accel < 0.0) {
+                accel = 0.0;
+            }
+        }
+
+        self.accel = accel;
+        self.vel = vel;
+        self.pos += self.vel * dt;
+
+        (self.pos, self.vel)
+    }
+
+    /// Current position (mm)
+    pub fn current(&self) -> f32 {
+        self.pos
+    }
+
+    /// Current velocity (mm/s)
+    pub fn current_velocity(&self) -> f32 {
+        self.vel
+    }
+
+    /// Within [`Self::SETTLE_EPSILON`] of the target with near-zero velocity
+    pub fn is_settled(&self) -> bool {
+        (self.target - self.pos).abs() < Self::SETTLE_EPSILON && self.vel.abs() < Self::SETTLE_EPSILON
+    }
+}
+
 /// Combined height motion generator
 ///
-/// Manages both trajectories and animations, providing a unified
-/// interface for height control.
+/// Manages trajectories, animations, and the [`ReferenceModel`] filter,
+/// providing a unified interface for height control.
 #[derive(Debug, Default)]
 pub struct HeightMotionGenerator {
     trajectory: Option<HeightTrajectory>,
     animation: Option<BobbleAnimation>,
+    reference: Option<ReferenceModel>,
     baseline_height: f32,
 }
 
@@ -247,26 +578,49 @@ impl HeightMotionGenerator {
         Self {
             trajectory: None,
             animation: None,
+            reference: None,
             baseline_height: super::constants::HEIGHT_FLOAT_MM,
         }
     }
 
-    /// Start a new trajectory (cancels any existing animation)
+    /// Start a new trajectory (cancels any existing animation/reference)
     pub fn start_trajectory(&mut self, traj: HeightTrajectory) {
         self.animation = None;
+        self.reference = None;
         self.trajectory = Some(traj);
     }
 
-    /// Start a new animation at current baseline
+    /// Start a new animation at current baseline (cancels any reference)
     pub fn start_animation(&mut self, anim: BobbleAnimation) {
         self.trajectory = None;
+        self.reference = None;
         self.animation = Some(anim);
     }
 
+    /// Command a [`ReferenceModel`]-filtered transition to `target_mm`
+    /// (cancels any trajectory/animation)
+    ///
+    /// Retargeting an already-running reference model is continuous --
+    /// `pos`/`vel` carry over -- so chained commands (e.g. float ->
+    /// charging -> float) blend instead of restarting from rest.
+    pub fn start_reference(&mut self, current_height: f32, target_mm: f32) {
+        self.trajectory = None;
+        self.animation = None;
+        match &mut self.reference {
+            Some(model) => model.set_target(target_mm),
+            None => {
+                let mut model = ReferenceModel::new(current_height);
+                model.set_target(target_mm);
+                self.reference = Some(model);
+            }
+        }
+    }
+
     /// Stop all motion, hold at current height
     pub fn stop(&mut self, current_height: f32) {
         self.trajectory = None;
         self.animation = None;
+        self.reference = None;
         self.baseline_height = current_height;
     }
 
@@ -274,7 +628,7 @@ impl HeightMotionGenerator {
     ///
     /// Returns (target_height, target_velocity)
     pub fn update(&mut self, dt: f32) -> (f32, f32) {
-        // Priority: trajectory > animation > baseline
+        // Priority: trajectory > animation > reference > baseline
 
         if let Some(ref mut traj) = self.trajectory {
             if traj.update(dt) {
@@ -295,12 +649,19 @@ impl HeightMotionGenerator {
             return (anim.current(), anim.current_velocity());
         }
 
+        if let Some(ref mut reference) = self.reference {
+            return reference.update(dt);
+        }
+
         (self.baseline_height, 0.0)
     }
 
-    /// Check if any motion is active
+    /// Check if any motion is active (a reference model that has settled at
+    /// its target no longer counts as active)
     pub fn is_active(&self) -> bool {
-        self.trajectory.is_some() || self.animation.is_some()
+        self.trajectory.is_some()
+            || self.animation.is_some()
+            || self.reference.as_ref().is_some_and(|r| !r.is_settled())
     }
 }
 
@@ -330,6 +691,41 @@ mod tests {
         assert!(v_mid.abs() > 5.0); // Should be moving fast
     }
 
+    #[test]
+    fn test_trajectory_jerk_limited_trapezoidal() {
+        // Long move: should reach cruise velocity and hold it for a while.
+        let traj = HeightTrajectory::new_jerk_limited(0.0, 100.0, 20.0, 40.0, 200.0);
+
+        // Endpoints land on start/target with zero velocity and accel.
+        assert!((traj.sample(0.0) - 0.0).abs() < 0.01);
+        assert!((traj.sample(traj.duration) - 100.0).abs() < 0.01);
+        assert!(traj.sample_velocity(0.0).abs() < 0.01);
+        assert!(traj.sample_acceleration(0.0).abs() < 0.01);
+
+        // Velocity/acceleration limits are respected everywhere we sample.
+        let mut t = 0.0;
+        while t < traj.duration {
+            assert!(traj.sample_velocity(t).abs() <= 20.0 + 0.01);
+            assert!(traj.sample_acceleration(t).abs() <= 40.0 + 0.01);
+            t += 0.01;
+        }
+
+        // Cruise phase: velocity should plateau near the max for a stretch.
+        let mid = traj.duration / 2.0;
+        assert!((traj.sample_velocity(mid).abs() - 20.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_trajectory_jerk_limited_short_move_triangular() {
+        // Short move: neither vmax nor amax should ever be reached, so the
+        // profile collapses to a triangular accel ramp with no cruise.
+        let traj = HeightTrajectory::new_jerk_limited(10.0, 10.5, 50.0, 50.0, 50.0);
+
+        assert!((traj.sample(0.0) - 10.0).abs() < 0.01);
+        assert!((traj.sample(traj.duration) - 10.5).abs() < 0.01);
+        assert!(traj.sample_velocity(traj.duration / 2.0).abs() < 50.0 + 0.01);
+    }
+
     #[test]
     fn test_bobble_animation() {
         let anim = BobbleAnimation::new(20.0, 5.0, 1.0);
@@ -364,4 +760,65 @@ mod tests {
         gen.update(0.6);
         assert!(!gen.is_active());
     }
+
+    #[test]
+    fn test_reference_model_converges_without_overshoot() {
+        let mut model = ReferenceModel::new(0.0);
+        model.set_target(10.0);
+
+        let mut peak = model.current();
+        for _ in 0..1000 {
+            let (pos, _) = model.update(0.01);
+            peak = peak.max(pos);
+        }
+
+        // Critically damped -- should approach but never exceed the target.
+        assert!(peak <= 10.0 + 0.01);
+        assert!((model.current() - 10.0).abs() < 0.01);
+        assert!(model.is_settled());
+    }
+
+    #[test]
+    fn test_reference_model_respects_rate_limits() {
+        let mut model = ReferenceModel::with_limits(0.0, 5.0, 20.0);
+        model.set_target(100.0);
+
+        for _ in 0..200 {
+            let (_, vel) = model.update(0.01);
+            assert!(vel.abs() <= 5.0 + 0.01);
+        }
+    }
+
+    #[test]
+    fn test_reference_model_retarget_is_continuous() {
+        let mut model = ReferenceModel::new(0.0);
+        model.set_target(10.0);
+        for _ in 0..100 {
+            model.update(0.01);
+        }
+        let (pos_before, vel_before) = (model.current(), model.current_velocity());
+
+        // Redirecting mid-flight must not reset pos/vel.
+        model.set_target(5.0);
+        let (pos_after, vel_after) = model.update(0.01);
+        assert!((pos_after - pos_before).abs() < 1.0);
+        assert!((vel_after - vel_before).abs() < 50.0);
+    }
+
+    #[test]
+    fn test_motion_generator_reference_transition() {
+        let mut gen = HeightMotionGenerator::new();
+
+        gen.start_reference(20.0, 5.0);
+        assert!(gen.is_active());
+
+        for _ in 0..1000 {
+            gen.update(0.01);
+        }
+
+        let (h, v) = gen.update(0.01);
+        assert!((h - 5.0).abs() < 0.01);
+        assert!(v.abs() < 0.01);
+        assert!(!gen.is_active());
+    }
 }
@@ -31,6 +31,10 @@
 
 pub mod levitation;
 pub mod error;
+pub mod bus;
+pub mod dac;
+pub mod display;
+pub mod units;
 
 // Re-exports
 pub use levitation::{
@@ -42,3 +46,4 @@ pub use levitation::{
     LevitationSafetyVerifier,
 };
 pub use error::{BaseError, BaseResult};
+pub use units::{ElectricPotential, Frequency, Length};
@@ -5,6 +5,7 @@
 //! # Tasks
 //!
 //! - `height_control_task`: 100Hz control loop for levitation
+//! - `display_task`: Local OLED status readout
 //! - `wpt_control_task`: Wireless power transfer management
 //! - `led_animator_task`: Base LED animation
 //! - `communication_task`: WebSocket to hub, protocol with orb
@@ -18,13 +19,26 @@ use esp_backtrace as _;
 use esp_println::println;
 
 use embassy_executor::Spawner;
+use embassy_futures::select::{select3, Either3};
 use embassy_time::{Duration, Timer, Instant};
+use esp_hal::gpio::{Input, Io, Pull};
+use esp_hal::i2c::I2c;
+use ssd1306::mode::BufferedGraphicsMode;
+use ssd1306::prelude::*;
+use ssd1306::{I2CDisplayInterface, Ssd1306};
 
 mod levitation;
 mod error;
+mod bus;
+mod dac;
+mod display;
+mod units;
 
-use levitation::{HeightController, LevitationMode, constants};
+use levitation::{HeightController, LevitationMode, constants, EMERGENCY_FLAG};
 use error::BaseResult;
+use bus::{HeightState, ThermalState, PowerState, HEIGHT_STATE, THERMAL_STATE, POWER_STATE};
+use dac::{DacOutput, Mcp4725Dac};
+use display::DisplaySnapshot;
 
 /// Main entry point
 #[esp_hal_embassy::main]
@@ -39,11 +53,37 @@ async fn main(spawner: Spawner) {
     // Initialize embassy async runtime
     esp_hal_embassy::init(peripherals.TIMG0);
 
+    let io = Io::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    // NTC comparator ALERT_L and WPT TX fault lines: both are active-low
+    // open-drain outputs from the sensor/controller, so pull up and watch
+    // for a falling edge rather than polling.
+    let ntc_alert = Input::new(io.pins.gpio4, Pull::Up);
+    let wpt_fault = Input::new(io.pins.gpio5, Pull::Up);
+
+    // Default DAC: MCP4725 over I2C. Boards without a spare I2C bus can
+    // swap in `dac::Ad5680Dac` (bit-banged soft-SPI) without touching
+    // `HeightController` -- it only ever deals in volts.
+    let i2c = I2c::new(peripherals.I2C0, io.pins.gpio21, io.pins.gpio22);
+    let dac = Mcp4725Dac::new(i2c, 0x60, 3.3);
+
+    // Status OLED on a second I2C bus (separate from the DAC's for now;
+    // a shared-bus manager can merge these once both drivers are proven out)
+    let oled_i2c = I2c::new(peripherals.I2C1, io.pins.gpio8, io.pins.gpio9);
+    let mut oled = Ssd1306::new(
+        I2CDisplayInterface::new(oled_i2c),
+        DisplaySize128x64,
+        DisplayRotation::Rotate0,
+    )
+    .into_buffered_graphics_mode();
+    oled.init().unwrap();
+
     println!("Hardware initialized");
 
     // Spawn tasks
-    spawner.spawn(height_control_task()).unwrap();
-    spawner.spawn(safety_monitor_task()).unwrap();
+    spawner.spawn(height_control_task(dac)).unwrap();
+    spawner.spawn(safety_monitor_task(ntc_alert, wpt_fault)).unwrap();
+    spawner.spawn(display_task(oled)).unwrap();
 
     // TODO: Add these tasks when HAL is available
     // spawner.spawn(wpt_control_task()).unwrap();
@@ -66,12 +106,18 @@ async fn main(spawner: Spawner) {
 /// computes the target height based on current mode, and sets the DAC
 /// output to command the HCNT module.
 #[embassy_executor::task]
-async fn height_control_task() {
+async fn height_control_task(mut dac: impl DacOutput + 'static) {
     println!("Height control task started");
 
     let mut controller = HeightController::new();
     let mut last_update = Instant::now();
 
+    // TODO: Read the EEPROM-persisted `CalibrationData`/`WptCalibrationData`
+    // here via `CalibrationData::from_bytes` once a NorFlash driver is
+    // wired up, falling back to `CalibrationData::default()` (as now) and
+    // running `CalibrationBuilder` to fit a fresh record when the orb is
+    // first placed on an uncalibrated base, then `controller.set_calibration(..)`.
+
     // Simulated initial state - orb placed on base
     Timer::after(Duration::from_secs(2)).await;
     controller.on_orb_placed();
@@ -94,9 +140,22 @@ async fn height_control_task() {
         // Run control loop
         match controller.update(adc_value, power_ok, coil_temp) {
             Ok((dac_voltage, wpt_freq)) => {
-                // TODO: Write DAC voltage via I2C to MCP4725
+                if let Err(e) = dac.set_voltage(dac_voltage) {
+                    println!("DAC write failed: {:?}", e);
+                }
                 // TODO: Update WPT frequency
 
+                // Publish live state for the safety monitor and any future
+                // comms/WPT tasks. Non-blocking: a slow subscriber just
+                // misses samples rather than stalling the 100Hz loop.
+                let state = controller.state();
+                bus::publish(&HEIGHT_STATE, HeightState {
+                    height_mm: state.height_mm,
+                    velocity_mm_s: state.velocity_mm_s,
+                    mode: controller.mode(),
+                    dac_voltage,
+                });
+
                 // Log state periodically
                 if now.as_millis() % 1000 < 10 {
                     let state = controller.state();
@@ -126,19 +185,110 @@ async fn height_control_task() {
 /// Safety monitor task - continuous background monitoring
 ///
 /// Watches for safety violations and triggers emergency procedures
-/// independent of the main control loop.
+/// independent of the main control loop. A 10Hz poll alone is too slow to
+/// catch a coil thermal runaway or a WPT foreign-object fault, so the two
+/// alert lines are `select`ed against directly and fire as soon as the
+/// hardware asserts them; the periodic tick covers everything else.
 #[embassy_executor::task]
-async fn safety_monitor_task() {
+async fn safety_monitor_task(mut ntc_alert: Input<'static>, mut wpt_fault: Input<'static>) {
     println!("Safety monitor task started");
 
+    let mut height_sub = HEIGHT_STATE.subscriber().unwrap();
+    let mut thermal_sub = THERMAL_STATE.subscriber().unwrap();
+    let mut power_sub = POWER_STATE.subscriber().unwrap();
+
+    let mut last_height = HeightState::default();
+    let mut last_thermal = ThermalState::default();
+    let mut last_power = PowerState::default();
+
     loop {
+        // Drain whatever's queued on each topic; we only care about the
+        // latest sample, not the history, so the safety monitor never
+        // polls hardware twice for state another task already owns.
+        while let Some(msg) = height_sub.try_next_message_pure() {
+            last_height = msg;
+        }
+        while let Some(msg) = thermal_sub.try_next_message_pure() {
+            last_thermal = msg;
+        }
+        while let Some(msg) = power_sub.try_next_message_pure() {
+            last_power = msg;
+        }
+
         // TODO: Check power supply voltage
-        // TODO: Check temperature sensors
         // TODO: Check Hall sensor validity
-        // TODO: Check WPT fault signals
 
-        // Run at 10Hz
-        Timer::after(Duration::from_millis(100)).await;
+        let _ = (last_height, last_thermal, last_power);
+
+        match select3(
+            ntc_alert.wait_for_falling_edge(),
+            wpt_fault.wait_for_falling_edge(),
+            Timer::after(Duration::from_millis(100)),
+        )
+        .await
+        {
+            Either3::First(()) => {
+                // Debounce ~1ms: a genuine thermal runaway alert stays
+                // asserted, a transient EMI glitch near the WPT coil does not.
+                Timer::after(Duration::from_millis(1)).await;
+                if ntc_alert.is_low() {
+                    println!("NTC over-temperature alert latched");
+                    EMERGENCY_FLAG.latch();
+                }
+            }
+            Either3::Second(()) => {
+                Timer::after(Duration::from_millis(1)).await;
+                if wpt_fault.is_low() {
+                    println!("WPT fault alert latched");
+                    EMERGENCY_FLAG.latch();
+                }
+            }
+            Either3::Third(()) => {
+                // Periodic watchdog tick; nothing to do beyond the topic
+                // drain above.
+            }
+        }
+    }
+}
+
+/// Display task - local OLED status readout
+///
+/// Redraws at a modest rate so it never competes with the 100Hz control
+/// loop. Subscribes to the height bus rather than reading hardware itself.
+#[embassy_executor::task]
+async fn display_task<DI>(mut oled: Ssd1306<DI, DisplaySize128x64, BufferedGraphicsMode<DisplaySize128x64>>)
+where
+    DI: WriteOnlyDataCommand + 'static,
+{
+    println!("Display task started");
+
+    let mut height_sub = HEIGHT_STATE.subscriber().unwrap();
+    let mut last_height = HeightState::default();
+    let mut flash_phase = false;
+
+    loop {
+        while let Some(msg) = height_sub.try_next_message_pure() {
+            last_height = msg;
+        }
+
+        let snapshot = DisplaySnapshot {
+            height_mm: last_height.height_mm,
+            velocity_mm_s: last_height.velocity_mm_s,
+            mode: last_height.mode,
+            dac_voltage: last_height.dac_voltage,
+            emergency: EMERGENCY_FLAG.is_set(),
+            flash_phase,
+        };
+        flash_phase = !flash_phase;
+
+        if let Err(_e) = display::render(&mut oled, &snapshot) {
+            println!("Display render failed");
+        }
+        if oled.flush().is_err() {
+            println!("Display flush failed");
+        }
+
+        Timer::after(Duration::from_millis(1000 / display::DISPLAY_REFRESH_HZ as u64)).await;
     }
 }
 
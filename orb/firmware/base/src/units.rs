@@ -0,0 +1,13 @@
+//! Type-safe physical quantities for the levitation/WPT control surface
+//!
+//! Thin `f32`, `no_std` `uom` re-exports so height, voltage, and frequency
+//! values can't be mixed up at a call site the way bare `f32`s can -- the
+//! same discipline the M-Labs thermostat firmware uses for its
+//! temperature/voltage signal path. These wrap the hot-path `f32` math in
+//! [`crate::levitation`]; they aren't meant to replace it there.
+
+pub use uom::si::f32::{ElectricPotential, Frequency, Length};
+
+pub use uom::si::electric_potential::volt;
+pub use uom::si::frequency::hertz;
+pub use uom::si::length::millimeter;